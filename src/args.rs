@@ -1,8 +1,20 @@
+use crate::blc::IoMode;
+use crate::eval::Strategy;
+use crate::parser::{NumeralEncoding, ParseOptions};
 use clap::Parser;
+use std::path::PathBuf;
 
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 pub struct Args {
+    /// Source file to run instead of starting the REPL
+    pub file: Option<PathBuf>,
+
+    /// Run FILE as a binary lambda calculus program on stdin, reading and
+    /// writing bits or bytes
+    #[arg(long, value_name = "MODE")]
+    pub blc: Option<IoMode>,
+
     /// Show unique ID after the variable name
     #[arg(short = 'u', long, default_value_t = false)]
     pub unique_id: bool,
@@ -14,4 +26,42 @@ pub struct Args {
     /// Print each step
     #[arg(short = 'p', long, default_value_t = false)]
     pub print_step: bool,
+
+    /// Print results using De Bruijn indices
+    #[arg(short = 'd', long, default_value_t = false)]
+    pub debruijn: bool,
+
+    /// Also perform eta-reduction (\x.f x => f)
+    #[arg(short = 'e', long, default_value_t = false)]
+    pub eta: bool,
+
+    /// Start without the standard prelude definitions (TRUE, ADD, ...)
+    #[arg(long, default_value_t = false)]
+    pub no_prelude: bool,
+
+    /// Encoding for integer literals (church, scott, parigot, binary)
+    #[arg(short = 'n', long, default_value_t = NumeralEncoding::Church)]
+    pub numerals: NumeralEncoding,
+
+    /// Read integer literals and add, sub, mul, eq, ifz as native primitives
+    #[arg(long, default_value_t = false)]
+    pub primitives: bool,
+
+    /// Show the inferred type of each expression next to its result
+    #[arg(long, default_value_t = false)]
+    pub types: bool,
+
+    /// Reduction strategy (normal, applicative, cbn, cbv, head)
+    #[arg(short = 's', long, default_value_t = Strategy::NormalOrder)]
+    pub strategy: Strategy,
+}
+
+impl Args {
+    /// Parser options selected by the command-line flags
+    pub fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            numerals: self.numerals,
+            primitives: self.primitives,
+        }
+    }
 }
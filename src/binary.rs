@@ -0,0 +1,373 @@
+//! Little-endian binary numerals
+//!
+//! A numeral is a chain of bits ending in `END`, least significant bit first,
+//! with each link a three-way case:
+//!
+//! - `END  = λe.λo.λi.e`
+//! - `O r  = λe.λo.λi.o r` (the number `2r`)
+//! - `I r  = λe.λo.λi.i r` (the number `2r + 1`)
+//!
+//! So 6 is `O (I (I END))`. The canonical form has no `O` right before `END`.
+//!
+//! Arithmetic recurses over the bits with `Y`, so it takes a number of steps
+//! proportional to the number of digits rather than to the value itself.
+
+use crate::church::{church_false, church_true, church_y};
+use crate::expr::{app, fun, var, Expr};
+
+pub fn binary_end() -> Box<Expr> {
+    // λe.λo.λi.e
+    fun(
+        "e".to_string(),
+        fun("o".to_string(), fun("i".to_string(), var("e".to_string()))),
+    )
+}
+
+/// Prepend one bit to the numeral `rest`, giving `2 * rest + bit`
+pub fn binary_bit(bit: bool, rest: Box<Expr>) -> Box<Expr> {
+    // λe.λo.λi.o rest  or  λe.λo.λi.i rest
+    let selector = if bit { "i" } else { "o" };
+    fun(
+        "e".to_string(),
+        fun(
+            "o".to_string(),
+            fun("i".to_string(), app(var(selector.to_string()), rest)),
+        ),
+    )
+}
+
+pub fn binary_numeral(n: u64) -> Box<Expr> {
+    let mut bits = Vec::new();
+    let mut rest = n;
+    while rest > 0 {
+        bits.push(rest & 1 == 1);
+        rest >>= 1;
+    }
+    bits.into_iter()
+        .rev()
+        .fold(binary_end(), |acc, bit| binary_bit(bit, acc))
+}
+
+/// `λr.λe.λo.λi.o r` or `λr.λe.λo.λi.i r`, the bit constructors as terms
+fn bit(bit: bool) -> Box<Expr> {
+    fun("r".to_string(), binary_bit(bit, var("r".to_string())))
+}
+
+/// `case n of END => e | O r => o r | I r => i r`
+fn case(n: Box<Expr>, e: Box<Expr>, o: Box<Expr>, i: Box<Expr>) -> Box<Expr> {
+    app(app(app(n, e), o), i)
+}
+
+/// `Y (λname.body)`
+fn recursive(name: &str, body: Box<Expr>) -> Box<Expr> {
+    app(church_y(), fun(name.to_string(), body))
+}
+
+pub fn binary_succ() -> Box<Expr> {
+    // Y (λsucc.λn.n (I END) (λr.I r) (λr.O (succ r)))
+    recursive(
+        "succ",
+        fun(
+            "n".to_string(),
+            case(
+                var("n".to_string()),
+                app(bit(true), binary_end()),
+                fun("r".to_string(), app(bit(true), var("r".to_string()))),
+                fun(
+                    "r".to_string(),
+                    app(
+                        bit(false),
+                        app(var("succ".to_string()), var("r".to_string())),
+                    ),
+                ),
+            ),
+        ),
+    )
+}
+
+/// `2n`, keeping zero as `END`
+fn double() -> Box<Expr> {
+    // λn.n END (λr.O n) (λr.O n)
+    let shifted = || fun("r".to_string(), app(bit(false), var("n".to_string())));
+    fun(
+        "n".to_string(),
+        case(var("n".to_string()), binary_end(), shifted(), shifted()),
+    )
+}
+
+pub fn binary_add() -> Box<Expr> {
+    // Y (λadd.λm.λn.m n
+    //     (λa.n (O a) (λb.O (add a b)) (λb.I (add a b)))
+    //     (λa.n (I a) (λb.I (add a b)) (λb.O (SUCC (add a b)))))
+    let sum = || {
+        app(
+            app(var("add".to_string()), var("a".to_string())),
+            var("b".to_string()),
+        )
+    };
+    let low_o = fun(
+        "a".to_string(),
+        case(
+            var("n".to_string()),
+            app(bit(false), var("a".to_string())),
+            fun("b".to_string(), app(bit(false), sum())),
+            fun("b".to_string(), app(bit(true), sum())),
+        ),
+    );
+    let low_i = fun(
+        "a".to_string(),
+        case(
+            var("n".to_string()),
+            app(bit(true), var("a".to_string())),
+            fun("b".to_string(), app(bit(true), sum())),
+            fun("b".to_string(), app(bit(false), app(binary_succ(), sum()))),
+        ),
+    );
+    recursive(
+        "add",
+        fun(
+            "m".to_string(),
+            fun(
+                "n".to_string(),
+                case(var("m".to_string()), var("n".to_string()), low_o, low_i),
+            ),
+        ),
+    )
+}
+
+pub fn binary_mult() -> Box<Expr> {
+    // Y (λmult.λm.λn.m END (λa.DOUBLE (mult a n)) (λa.ADD n (DOUBLE (mult a n))))
+    let doubled = || {
+        app(
+            double(),
+            app(
+                app(var("mult".to_string()), var("a".to_string())),
+                var("n".to_string()),
+            ),
+        )
+    };
+    recursive(
+        "mult",
+        fun(
+            "m".to_string(),
+            fun(
+                "n".to_string(),
+                case(
+                    var("m".to_string()),
+                    binary_end(),
+                    fun("a".to_string(), doubled()),
+                    fun(
+                        "a".to_string(),
+                        app(app(binary_add(), var("n".to_string())), doubled()),
+                    ),
+                ),
+            ),
+        ),
+    )
+}
+
+/// One of the three orderings `λl.λe.λg.l`, `λl.λe.λg.e` and `λl.λe.λg.g`
+fn ordering(which: &str) -> Box<Expr> {
+    fun(
+        "l".to_string(),
+        fun(
+            "e".to_string(),
+            fun("g".to_string(), var(which.to_string())),
+        ),
+    )
+}
+
+pub fn binary_compare() -> Box<Expr> {
+    // λm.λn.cmp m n EQ, returning λl.λe.λg.l, λl.λe.λg.e or λl.λe.λg.g
+    //
+    // `cmp m n k` walks both numerals from the least significant bit, with
+    // `k` the ordering of the bits seen so far. A higher bit that differs
+    // overrides it, and a missing bit counts as `O`, so numerals with
+    // leading zeros still compare correctly.
+    let next =
+        |a: Box<Expr>, b: Box<Expr>, k: Box<Expr>| app(app(app(var("cmp".to_string()), a), b), k);
+    let low_end = case(
+        var("n".to_string()),
+        var("k".to_string()),
+        fun(
+            "b".to_string(),
+            next(binary_end(), var("b".to_string()), var("k".to_string())),
+        ),
+        fun(
+            "b".to_string(),
+            next(binary_end(), var("b".to_string()), ordering("l")),
+        ),
+    );
+    let low_o = fun(
+        "a".to_string(),
+        case(
+            var("n".to_string()),
+            next(var("a".to_string()), binary_end(), var("k".to_string())),
+            fun(
+                "b".to_string(),
+                next(
+                    var("a".to_string()),
+                    var("b".to_string()),
+                    var("k".to_string()),
+                ),
+            ),
+            fun(
+                "b".to_string(),
+                next(var("a".to_string()), var("b".to_string()), ordering("l")),
+            ),
+        ),
+    );
+    let low_i = fun(
+        "a".to_string(),
+        case(
+            var("n".to_string()),
+            next(var("a".to_string()), binary_end(), ordering("g")),
+            fun(
+                "b".to_string(),
+                next(var("a".to_string()), var("b".to_string()), ordering("g")),
+            ),
+            fun(
+                "b".to_string(),
+                next(
+                    var("a".to_string()),
+                    var("b".to_string()),
+                    var("k".to_string()),
+                ),
+            ),
+        ),
+    );
+    let cmp = recursive(
+        "cmp",
+        fun(
+            "m".to_string(),
+            fun(
+                "n".to_string(),
+                fun(
+                    "k".to_string(),
+                    case(var("m".to_string()), low_end, low_o, low_i),
+                ),
+            ),
+        ),
+    );
+    fun(
+        "m".to_string(),
+        fun(
+            "n".to_string(),
+            app(
+                app(app(cmp, var("m".to_string())), var("n".to_string())),
+                ordering("e"),
+            ),
+        ),
+    )
+}
+
+/// Turn an ordering into a Church boolean, one result per ordering
+fn on_ordering(less: Box<Expr>, equal: Box<Expr>, greater: Box<Expr>) -> Box<Expr> {
+    // λm.λn.COMPARE m n less equal greater
+    fun(
+        "m".to_string(),
+        fun(
+            "n".to_string(),
+            app(
+                app(
+                    app(
+                        app(
+                            app(binary_compare(), var("m".to_string())),
+                            var("n".to_string()),
+                        ),
+                        less,
+                    ),
+                    equal,
+                ),
+                greater,
+            ),
+        ),
+    )
+}
+
+pub fn binary_leq() -> Box<Expr> {
+    on_ordering(church_true(), church_true(), church_false())
+}
+
+pub fn binary_eq() -> Box<Expr> {
+    on_ordering(church_false(), church_true(), church_false())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{decode_binary, decode_bool};
+    use crate::test_support::eval_to_normal;
+
+    fn apply2(f: Box<Expr>, m: u64, n: u64) -> Expr {
+        eval_to_normal(*app(app(f, binary_numeral(m)), binary_numeral(n)))
+    }
+
+    #[test]
+    fn test_numeral_bits() {
+        assert_eq!(binary_numeral(0), binary_end());
+        // 2 = O (I END)
+        assert_eq!(
+            binary_numeral(2),
+            binary_bit(false, binary_bit(true, binary_end()))
+        );
+        assert_eq!(
+            binary_numeral(2).format(false),
+            "(\\e.(\\o.(\\i.(o (\\e.(\\o.(\\i.(i (\\e.(\\o.(\\i.e)))))))))))"
+        );
+    }
+
+    #[test]
+    fn test_succ() {
+        for n in [0, 1, 6, 7] {
+            let result = eval_to_normal(*app(binary_succ(), binary_numeral(n)));
+            assert!(result.alpha_eq(&binary_numeral(n + 1)), "SUCC {}", n);
+        }
+    }
+
+    #[test]
+    fn test_add() {
+        for (m, n) in [(0, 5), (5, 0), (6, 7), (13, 19)] {
+            let result = apply2(binary_add(), m, n);
+            assert!(result.alpha_eq(&binary_numeral(m + n)), "ADD {} {}", m, n);
+        }
+    }
+
+    #[test]
+    fn test_mult() {
+        for (m, n) in [(0, 5), (5, 0), (6, 7), (12, 34)] {
+            let result = apply2(binary_mult(), m, n);
+            assert_eq!(
+                decode_binary(&result),
+                Some((m * n) as i64),
+                "MULT {} {}",
+                m,
+                n
+            );
+            assert!(
+                result.alpha_eq(&binary_numeral(m * n)),
+                "MULT {} {} is canonical",
+                m,
+                n
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        for (m, n) in [(0, 0), (0, 3), (3, 0), (6, 6), (6, 7), (9, 4)] {
+            let result = apply2(binary_leq(), m, n);
+            assert_eq!(decode_bool(&result), Some(m <= n), "LEQ {} {}", m, n);
+            let result = apply2(binary_eq(), m, n);
+            assert_eq!(decode_bool(&result), Some(m == n), "EQ {} {}", m, n);
+        }
+    }
+
+    #[test]
+    fn test_compare_leading_zeros() {
+        // 2 written as O (I (O END)) still equals 2
+        let padded = binary_bit(false, binary_bit(true, binary_bit(false, binary_end())));
+        let result = eval_to_normal(*app(app(binary_eq(), padded), binary_numeral(2)));
+        assert_eq!(decode_bool(&result), Some(true));
+    }
+}
@@ -0,0 +1,412 @@
+//! John Tromp's binary lambda calculus
+//!
+//! A closed term is written in bits using 1-based De Bruijn indices:
+//! `00 M` for an abstraction, `01 M N` for an application and `1^i 0` for
+//! the variable with index `i`. Programs read their input and produce their
+//! output as lists in Tromp's convention: `cons h t = \z.z h t`, `nil` is
+//! false, and a bit 0 is true while 1 is false. In byte mode each element is
+//! itself a list of 8 bits, most significant first.
+
+use crate::debruijn::DbExpr;
+use crate::eval::{NameSupply, Strategy};
+use crate::expr::Expr;
+use anyhow::Result;
+
+/// Step limit used when running programs from the command line
+pub const DEFAULT_STEPS: usize = 1_000_000;
+
+/// Longest input [`run`] accepts, counted in input bytes in either mode
+pub const MAX_INPUT: usize = 1 << 16;
+
+/// Stack size of the thread [`run`] reduces on
+///
+/// The input list nests a few levels per element, and reduction, comparison
+/// and dropping all recurse through it, so the default stack of a thread
+/// overflows on inputs of a few kilobytes.
+const STACK_SIZE: usize = 1 << 30;
+
+/// Whether a program reads and writes bits or bytes
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IoMode {
+    /// Each input byte gives one bit (its lowest), and output bits are
+    /// written as the characters `0` and `1`
+    #[default]
+    Bits,
+    /// Input and output are raw bytes
+    Bytes,
+}
+
+impl std::fmt::Display for IoMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IoMode::Bits => write!(f, "bits"),
+            IoMode::Bytes => write!(f, "bytes"),
+        }
+    }
+}
+
+impl std::str::FromStr for IoMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "bits" => Ok(IoMode::Bits),
+            "bytes" => Ok(IoMode::Bytes),
+            _ => Err(anyhow::anyhow!(
+                "unknown I/O mode: {} (expected bits or bytes)",
+                s
+            )),
+        }
+    }
+}
+
+/// Encode a closed term as a string of `0` and `1`
+pub fn encode(expr: &Expr) -> Result<String> {
+    fn go(expr: &DbExpr, out: &mut String) -> Result<()> {
+        match expr {
+            DbExpr::Var(index) => {
+                out.extend(std::iter::repeat_n('1', index + 1));
+                out.push('0');
+            }
+            DbExpr::Fun(_, body) => {
+                out.push_str("00");
+                go(body, out)?;
+            }
+            DbExpr::App(lhs, rhs) => {
+                out.push_str("01");
+                go(lhs, out)?;
+                go(rhs, out)?;
+            }
+            DbExpr::Free(name) => {
+                return Err(anyhow::anyhow!("free variable {} cannot be encoded", name));
+            }
+            DbExpr::Lit(_) | DbExpr::Prim(_) => {
+                return Err(anyhow::anyhow!("primitives cannot be encoded"));
+            }
+        }
+        Ok(())
+    }
+    let mut out = String::new();
+    go(&DbExpr::from_expr(expr), &mut out)?;
+    Ok(out)
+}
+
+/// Decode one term from the start of `bits`, returning it and the number of bits used
+fn decode_prefix(bits: &[bool]) -> Result<(DbExpr, usize)> {
+    fn go(bits: &[bool], pos: &mut usize, depth: usize) -> Result<DbExpr> {
+        let mut next = || {
+            let bit = bits.get(*pos).copied();
+            *pos += 1;
+            bit.ok_or_else(|| anyhow::anyhow!("unexpected end of bits"))
+        };
+        if next()? {
+            let mut index = 0;
+            while next()? {
+                index += 1;
+            }
+            if index >= depth {
+                return Err(anyhow::anyhow!(
+                    "variable {} refers outside the term at bit {}",
+                    index + 1,
+                    *pos
+                ));
+            }
+            Ok(DbExpr::Var(index))
+        } else if next()? {
+            let lhs = go(bits, pos, depth)?;
+            let rhs = go(bits, pos, depth)?;
+            Ok(DbExpr::App(Box::new(lhs), Box::new(rhs)))
+        } else {
+            // Hints a, b, c, ... by depth, primed when converting back if needed
+            let hint = ((b'a' + (depth % 26) as u8) as char).to_string();
+            Ok(DbExpr::Fun(hint, Box::new(go(bits, pos, depth + 1)?)))
+        }
+    }
+    let mut pos = 0;
+    let term = go(bits, &mut pos, 0)?;
+    Ok((term, pos))
+}
+
+/// Read a string of `0` and `1` into bits, ignoring whitespace
+fn parse_bits(bits: &str) -> Result<Vec<bool>> {
+    bits.chars()
+        .filter(|ch| !ch.is_whitespace())
+        .map(|ch| match ch {
+            '0' => Ok(false),
+            '1' => Ok(true),
+            _ => Err(anyhow::anyhow!("unexpected character in bits: {}", ch)),
+        })
+        .collect()
+}
+
+/// Decode a term written as `0` and `1` characters (whitespace is ignored)
+pub fn decode(bits: &str) -> Result<Expr> {
+    let bits = parse_bits(bits)?;
+    let (term, used) = decode_prefix(&bits)?;
+    if used != bits.len() {
+        return Err(anyhow::anyhow!(
+            "{} bits left after the term",
+            bits.len() - used
+        ));
+    }
+    Ok(term.to_expr(&mut NameSupply::new()))
+}
+
+/// Pack bits into bytes, most significant bit first, padding with zeros
+pub fn pack(bits: &str) -> Result<Vec<u8>> {
+    Ok(parse_bits(bits)?
+        .chunks(8)
+        .map(|chunk| {
+            chunk
+                .iter()
+                .enumerate()
+                .fold(0, |byte, (i, &bit)| byte | ((bit as u8) << (7 - i)))
+        })
+        .collect())
+}
+
+/// Unpack bytes into a string of `0` and `1`, most significant bit first
+pub fn unpack(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:08b}", byte)).collect()
+}
+
+/// Read a program file, either `0`/`1` text or packed bytes
+///
+/// Packed programs may be followed by up to 7 padding bits.
+pub fn read_program(contents: &[u8]) -> Result<Expr> {
+    if contents
+        .iter()
+        .all(|&b| b == b'0' || b == b'1' || b.is_ascii_whitespace())
+    {
+        return decode(std::str::from_utf8(contents)?);
+    }
+    let bits = parse_bits(&unpack(contents))?;
+    let (term, used) = decode_prefix(&bits)?;
+    if bits.len() - used >= 8 {
+        return Err(anyhow::anyhow!(
+            "{} bits left after the program",
+            bits.len() - used
+        ));
+    }
+    Ok(term.to_expr(&mut NameSupply::new()))
+}
+
+fn bit_term(bit: bool) -> DbExpr {
+    // 0 is true (\x.\y.x), 1 is false (\x.\y.y)
+    let index = if bit { 0 } else { 1 };
+    DbExpr::Fun(
+        "x".to_string(),
+        Box::new(DbExpr::Fun("y".to_string(), Box::new(DbExpr::Var(index)))),
+    )
+}
+
+/// The empty list, false, which is also the bit 1
+fn nil() -> DbExpr {
+    bit_term(true)
+}
+
+fn list_term(items: impl DoubleEndedIterator<Item = DbExpr>) -> DbExpr {
+    items.rev().fold(nil(), |tail, head| {
+        DbExpr::Fun(
+            "z".to_string(),
+            Box::new(DbExpr::App(
+                Box::new(DbExpr::App(Box::new(DbExpr::Var(0)), Box::new(head))),
+                Box::new(tail),
+            )),
+        )
+    })
+}
+
+/// Check whether `expr` refers to any of the `count` binders starting
+/// `index` levels up
+fn refers_to(expr: &DbExpr, index: usize, count: usize) -> bool {
+    match expr {
+        DbExpr::Var(i) => (index..index + count).contains(i),
+        DbExpr::Fun(_, body) => refers_to(body, index + 1, count),
+        DbExpr::App(lhs, rhs) => refers_to(lhs, index, count) || refers_to(rhs, index, count),
+        DbExpr::Free(_) | DbExpr::Lit(_) | DbExpr::Prim(_) => false,
+    }
+}
+
+fn read_bit(expr: &DbExpr) -> Option<bool> {
+    if *expr == bit_term(false) {
+        Some(false)
+    } else if *expr == bit_term(true) {
+        Some(true)
+    } else {
+        None
+    }
+}
+
+fn read_list(expr: &DbExpr) -> Option<Vec<DbExpr>> {
+    let mut items = Vec::new();
+    let mut current = expr;
+    // The tail of each cell sits under the `\z` of every cell before it, so
+    // an element must not refer to any of those binders either
+    let mut depth = 0;
+    loop {
+        if *current == nil() {
+            return Some(items);
+        }
+        let DbExpr::Fun(_, body) = current else {
+            return None;
+        };
+        let DbExpr::App(lhs, tail) = body.as_ref() else {
+            return None;
+        };
+        let DbExpr::App(z, head) = lhs.as_ref() else {
+            return None;
+        };
+        depth += 1;
+        if **z != DbExpr::Var(0) || refers_to(head, 0, depth) {
+            return None;
+        }
+        items.push(head.shift(-(depth as isize), 0));
+        current = tail;
+    }
+}
+
+/// Run a program on `input` in normal order and return its output
+///
+/// Fails if the input is longer than [`MAX_INPUT`], if the program does not
+/// finish within `max_steps` steps or if its output is not a list of bits or
+/// bytes.
+pub fn run(program: &Expr, input: &[u8], mode: IoMode, max_steps: usize) -> Result<Vec<u8>> {
+    if input.len() > MAX_INPUT {
+        return Err(anyhow::anyhow!(
+            "input too long: {} bytes (at most {})",
+            input.len(),
+            MAX_INPUT
+        ));
+    }
+    std::thread::scope(|scope| {
+        std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, || run_on_stack(program, input, mode, max_steps))?
+            .join()
+            .map_err(|_| anyhow::anyhow!("program panicked"))?
+    })
+}
+
+fn run_on_stack(program: &Expr, input: &[u8], mode: IoMode, max_steps: usize) -> Result<Vec<u8>> {
+    let input = match mode {
+        IoMode::Bits => list_term(input.iter().map(|byte| bit_term(byte & 1 == 1))),
+        IoMode::Bytes => list_term(
+            input
+                .iter()
+                .map(|byte| list_term((0..8).rev().map(|i| bit_term(byte >> i & 1 == 1)))),
+        ),
+    };
+    let term = DbExpr::App(Box::new(DbExpr::from_expr(program)), Box::new(input));
+    let (output, exceeded) = term.normalize(Strategy::NormalOrder, max_steps);
+    if exceeded {
+        return Err(anyhow::anyhow!(
+            "program did not finish within {} steps",
+            max_steps
+        ));
+    }
+
+    let malformed = || anyhow::anyhow!("output is not a list of {}", mode);
+    let items = read_list(&output).ok_or_else(malformed)?;
+    match mode {
+        IoMode::Bits => items
+            .iter()
+            .map(|item| read_bit(item).map(|bit| if bit { b'1' } else { b'0' }))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(malformed),
+        IoMode::Bytes => items
+            .iter()
+            .map(|item| {
+                let bits = read_list(item).filter(|bits| bits.len() == 8)?;
+                bits.iter()
+                    .try_fold(0u8, |byte, bit| Some(byte << 1 | read_bit(bit)? as u8))
+            })
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(malformed),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn run_str(program: &str, input: &[u8], mode: IoMode) -> Result<Vec<u8>> {
+        run(&parse(program).unwrap(), input, mode, 10000)
+    }
+
+    #[test]
+    fn test_encode_known_terms() {
+        assert_eq!(encode(&parse("\\x.x").unwrap()).unwrap(), "0010");
+        assert_eq!(encode(&parse("\\x.\\y.x").unwrap()).unwrap(), "0000110");
+        assert_eq!(
+            encode(&parse("\\x.\\y.\\z.x z (y z)").unwrap()).unwrap(),
+            "00000001011110100111010"
+        );
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        assert!(encode(&parse("\\x.y").unwrap()).is_err());
+        assert!(encode(&Expr::Lit(1)).is_err());
+    }
+
+    #[test]
+    fn test_decode_round_trip() {
+        for input in ["\\x.x", "\\f.\\x.f (f x)", "(\\x.x x) (\\y.\\z.y z z)"] {
+            let expr = parse(input).unwrap();
+            let decoded = decode(&encode(&expr).unwrap()).unwrap();
+            assert!(decoded.alpha_eq(&expr), "{}", input);
+        }
+        assert_eq!(decode("00 00 110").unwrap().format(false), "(\\a.(\\b.a))");
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode("0011110").is_err());
+        assert!(decode("0001").is_err());
+        assert!(decode("001000").is_err());
+        assert!(decode("0021").is_err());
+    }
+
+    #[test]
+    fn test_pack_and_read_program() {
+        let bits = encode(&parse("\\x.\\y.\\z.x z (y z)").unwrap()).unwrap();
+        let packed = pack(&bits).unwrap();
+        assert_eq!(packed.len(), 3);
+        assert_eq!(unpack(&packed)[..bits.len()], bits);
+        let from_bytes = read_program(&packed).unwrap();
+        let from_text = read_program(format!("{}\n", bits).as_bytes()).unwrap();
+        assert!(from_bytes.alpha_eq(&from_text));
+    }
+
+    #[test]
+    fn test_run_bits() {
+        assert_eq!(run_str("\\l.l", b"0110", IoMode::Bits).unwrap(), b"0110");
+        // Prepend a 0 bit
+        assert_eq!(
+            run_str("\\l.\\z.z (\\x.\\y.x) l", b"1", IoMode::Bits).unwrap(),
+            b"01"
+        );
+        assert!(run_str("\\l.\\x.x", b"1", IoMode::Bits).is_err());
+        assert!(run_str("\\l.(\\x.x x) (\\x.x x)", b"", IoMode::Bits).is_err());
+    }
+
+    #[test]
+    fn test_run_bytes() {
+        assert_eq!(run_str("\\l.l", b"hi", IoMode::Bytes).unwrap(), b"hi");
+        // Keep only the first byte
+        let head = "\\l.\\z.z (l (\\h.\\t.h)) (\\x.\\y.y)";
+        assert_eq!(run_str(head, b"hi", IoMode::Bytes).unwrap(), b"h");
+    }
+
+    #[test]
+    fn test_run_large_input() {
+        let bits: Vec<u8> = (0..MAX_INPUT).map(|i| b"01"[i % 3 % 2]).collect();
+        assert_eq!(run_str("\\l.l", &bits, IoMode::Bits).unwrap(), bits);
+        let bytes: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+        assert_eq!(run_str("\\l.l", &bytes, IoMode::Bytes).unwrap(), bytes);
+        let too_long = vec![b'0'; MAX_INPUT + 1];
+        assert!(run_str("\\l.l", &too_long, IoMode::Bits).is_err());
+    }
+}
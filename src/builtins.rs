@@ -0,0 +1,219 @@
+//! Rust functions that lambda terms can call by name
+//!
+//! A builtin is referenced as a free variable. Once it is applied to as many
+//! arguments as its arity, the evaluator reduces each argument to normal form
+//! (in normal order, whatever the strategy) and then calls the Rust closure in
+//! place of the whole application.
+
+use crate::expr::Expr;
+use std::collections::BTreeMap;
+
+/// Error raised while evaluating a term
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EvalError {
+    /// A builtin rejected its arguments or failed
+    Builtin { name: String, message: String },
+}
+
+impl std::fmt::Display for EvalError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EvalError::Builtin { name, message } => write!(f, "builtin {}: {}", name, message),
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+/// The Rust side of a builtin: its normalized arguments in, a term or an error message out
+pub type HostFn = dyn Fn(&[Expr]) -> Result<Expr, String> + Send + Sync;
+
+struct Builtin {
+    arity: usize,
+    func: Box<HostFn>,
+}
+
+/// Named Rust closures available to the evaluator
+#[derive(Default)]
+pub struct Builtins {
+    fns: BTreeMap<String, Builtin>,
+}
+
+impl Builtins {
+    pub fn new() -> Self {
+        Builtins {
+            fns: BTreeMap::new(),
+        }
+    }
+
+    /// Register `func` under `name`, replacing any builtin of the same name
+    ///
+    /// With arity 0 the closure is called every time the name is reduced,
+    /// which suits things like random numbers. Closures must be `Send + Sync`
+    /// so that a registry, and reducers using it, can be shared across threads.
+    pub fn register<F>(&mut self, name: impl Into<String>, arity: usize, func: F)
+    where
+        F: Fn(&[Expr]) -> Result<Expr, String> + Send + Sync + 'static,
+    {
+        self.fns.insert(
+            name.into(),
+            Builtin {
+                arity,
+                func: Box::new(func),
+            },
+        );
+    }
+
+    /// Number of arguments the builtin `name` takes, if it is registered
+    pub fn arity(&self, name: &str) -> Option<usize> {
+        self.fns.get(name).map(|builtin| builtin.arity)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fns.is_empty()
+    }
+
+    /// Iterate over the registered names in order
+    pub fn names(&self) -> impl Iterator<Item = &String> {
+        self.fns.keys()
+    }
+
+    /// Call the builtin `name`, which must be registered
+    pub(crate) fn call(&self, name: &str, args: &[Expr]) -> Result<Expr, EvalError> {
+        (self.fns[name].func)(args).map_err(|message| EvalError::Builtin {
+            name: name.to_string(),
+            message,
+        })
+    }
+}
+
+impl std::fmt::Debug for Builtins {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_map()
+            .entries(self.fns.iter().map(|(name, builtin)| (name, builtin.arity)))
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::church::church_numeral;
+    use crate::decode::decode_nat;
+    use crate::eval::{bind_vars, NameSupply, Reducer, Strategy};
+    use crate::parser::parse;
+    use std::sync::atomic::{AtomicI64, Ordering};
+    use std::sync::{Arc, Mutex};
+
+    fn run(input: &str, builtins: &Builtins, strategy: Strategy) -> Result<Expr, EvalError> {
+        let expr = bind_vars(*parse(input).unwrap(), &mut NameSupply::new());
+        let reducer = Reducer::new(strategy, false).with_builtins(builtins);
+        Ok(reducer.normalize(expr, 1000)?.0)
+    }
+
+    fn doubler() -> Builtins {
+        let mut builtins = Builtins::new();
+        builtins.register("double", 1, |args| {
+            let n = decode_nat(&args[0]).ok_or("expected a Church numeral")?;
+            Ok(*church_numeral(n * 2))
+        });
+        builtins
+    }
+
+    #[test]
+    fn test_builtin_sees_normalized_arguments() {
+        // The argument is only a numeral once (\n.n) 3 is reduced
+        let result = run("double ((\\n.n) 3)", &doubler(), Strategy::NormalOrder).unwrap();
+        assert_eq!(decode_nat(&result), Some(6));
+        let result = run("double ((\\n.n) 3)", &doubler(), Strategy::CallByName).unwrap();
+        assert_eq!(decode_nat(&result), Some(6));
+    }
+
+    #[test]
+    fn test_builtin_arguments_are_normalized_under_lambdas() {
+        // Weak strategies leave \f.(\g.g) f alone, but the closure sees \f.f
+        let mut builtins = Builtins::new();
+        builtins.register("show", 1, |args| Ok(Expr::Lit(args[0].size() as i64)));
+        for strategy in Strategy::ALL {
+            let result = run("show (\\f.(\\g.g) f)", &builtins, strategy).unwrap();
+            assert_eq!(result, Expr::Lit(2), "strategy {}", strategy);
+        }
+    }
+
+    #[test]
+    fn test_builtin_error() {
+        let err = run("double (\\x.x)", &doubler(), Strategy::NormalOrder).unwrap_err();
+        assert_eq!(
+            err,
+            EvalError::Builtin {
+                name: "double".to_string(),
+                message: "expected a Church numeral".to_string(),
+            }
+        );
+        assert_eq!(err.to_string(), "builtin double: expected a Church numeral");
+    }
+
+    #[test]
+    fn test_builtin_needs_all_arguments() {
+        let mut builtins = Builtins::new();
+        builtins.register("pair", 2, |args| Ok(args[1].clone()));
+        assert_eq!(
+            run("pair a", &builtins, Strategy::NormalOrder)
+                .unwrap()
+                .format(false),
+            "(pair a)"
+        );
+        assert_eq!(
+            run("pair a b c", &builtins, Strategy::NormalOrder)
+                .unwrap()
+                .format(false),
+            "(b c)"
+        );
+    }
+
+    #[test]
+    fn test_bound_names_shadow_builtins() {
+        let result = run("(\\double.double 1) f", &doubler(), Strategy::NormalOrder).unwrap();
+        assert_eq!(result.format(false), "(f (\\f.(\\x.(f x))))");
+    }
+
+    #[test]
+    fn test_builtin_closures_keep_state() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let counter = Arc::new(AtomicI64::new(0));
+        let mut builtins = Builtins::new();
+        let sink = log.clone();
+        builtins.register("log", 1, move |args| {
+            sink.lock().unwrap().push(args[0].format(false));
+            Ok(args[0].clone())
+        });
+        let source = counter.clone();
+        builtins.register("next", 0, move |_| {
+            Ok(Expr::Lit(source.fetch_add(1, Ordering::SeqCst) + 1))
+        });
+        let result = run("log ((\\x.x) y)", &builtins, Strategy::NormalOrder).unwrap();
+        assert_eq!(result.format(false), "y");
+        assert_eq!(*log.lock().unwrap(), vec!["y".to_string()]);
+        let result = run("f next next", &builtins, Strategy::NormalOrder).unwrap();
+        assert_eq!(result.format(false), "((f 1) 2)");
+        assert_eq!(counter.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_reducers_can_be_shared_across_threads() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Builtins>();
+        assert_send_sync::<Reducer>();
+        assert_send_sync::<crate::eval::Steps>();
+
+        let builtins = doubler();
+        let reducer = Reducer::new(Strategy::NormalOrder, false).with_builtins(&builtins);
+        std::thread::scope(|scope| {
+            let handle = scope.spawn(|| {
+                let expr = bind_vars(*parse("double 2").unwrap(), &mut NameSupply::new());
+                reducer.normalize(expr, 100).unwrap().0
+            });
+            assert_eq!(decode_nat(&handle.join().unwrap()), Some(4));
+        });
+    }
+}
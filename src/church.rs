@@ -1,7 +1,9 @@
-//! Church encodings for booleans and numerals in lambda calculus
+//! Church encodings for booleans, numerals, pairs and fixpoint combinators
 
 use crate::expr::{app, fun, var, Expr};
 
+pub mod list;
+
 // Church Booleans
 pub fn church_true() -> Box<Expr> {
     // λx.λy.x
@@ -28,6 +30,16 @@ pub fn church_and(p: Box<Expr>, q: Box<Expr>) -> Box<Expr> {
     app(app(p, q), church_false())
 }
 
+pub fn church_or(p: Box<Expr>, q: Box<Expr>) -> Box<Expr> {
+    // λp.λq.p p q  =>  p TRUE q
+    app(app(p, church_true()), q)
+}
+
+pub fn church_xor(p: Box<Expr>, q: Box<Expr>) -> Box<Expr> {
+    // λp.λq.p (NOT q) q
+    app(app(p, church_not(q.clone())), q)
+}
+
 // Church Numerals
 pub fn church_zero() -> Box<Expr> {
     // λf.λx.x
@@ -45,35 +57,13 @@ pub fn church_one() -> Box<Expr> {
     )
 }
 
-pub fn church_two() -> Box<Expr> {
-    // λf.λx.f (f x)
-    fun(
-        "f".to_string(),
-        fun(
-            "x".to_string(),
-            app(
-                var("f".to_string()),
-                app(var("f".to_string()), var("x".to_string())),
-            ),
-        ),
-    )
-}
-
-pub fn church_three() -> Box<Expr> {
-    // λf.λx.f (f (f x))
-    fun(
-        "f".to_string(),
-        fun(
-            "x".to_string(),
-            app(
-                var("f".to_string()),
-                app(
-                    var("f".to_string()),
-                    app(var("f".to_string()), var("x".to_string())),
-                ),
-            ),
-        ),
-    )
+pub fn church_numeral(n: u64) -> Box<Expr> {
+    // λf.λx.f (f ... (f x))
+    let mut body = var("x".to_string());
+    for _ in 0..n {
+        body = app(var("f".to_string()), body);
+    }
+    fun("f".to_string(), fun("x".to_string(), body))
 }
 
 pub fn church_succ() -> Box<Expr> {
@@ -147,42 +137,165 @@ pub fn church_is_zero() -> Box<Expr> {
     )
 }
 
+pub fn church_pred() -> Box<Expr> {
+    // λn.λf.λx.n (λg.λh.h (g f)) (λu.x) (λu.u)
+    fun(
+        "n".to_string(),
+        fun(
+            "f".to_string(),
+            fun(
+                "x".to_string(),
+                app(
+                    app(
+                        app(
+                            var("n".to_string()),
+                            fun(
+                                "g".to_string(),
+                                fun(
+                                    "h".to_string(),
+                                    app(
+                                        var("h".to_string()),
+                                        app(var("g".to_string()), var("f".to_string())),
+                                    ),
+                                ),
+                            ),
+                        ),
+                        fun("u".to_string(), var("x".to_string())),
+                    ),
+                    fun("u".to_string(), var("u".to_string())),
+                ),
+            ),
+        ),
+    )
+}
+
+pub fn church_sub() -> Box<Expr> {
+    // λm.λn.n PRED m
+    fun(
+        "m".to_string(),
+        fun(
+            "n".to_string(),
+            app(
+                app(var("n".to_string()), church_pred()),
+                var("m".to_string()),
+            ),
+        ),
+    )
+}
+
+pub fn church_exp() -> Box<Expr> {
+    // λm.λn.n m  =>  m to the power n
+    fun(
+        "m".to_string(),
+        fun(
+            "n".to_string(),
+            app(var("n".to_string()), var("m".to_string())),
+        ),
+    )
+}
+
+pub fn church_leq() -> Box<Expr> {
+    // λm.λn.IS_ZERO (SUB m n)
+    fun(
+        "m".to_string(),
+        fun(
+            "n".to_string(),
+            app(
+                church_is_zero(),
+                app(
+                    app(church_sub(), var("m".to_string())),
+                    var("n".to_string()),
+                ),
+            ),
+        ),
+    )
+}
+
+pub fn church_eq() -> Box<Expr> {
+    // λm.λn.AND (LEQ m n) (LEQ n m)
+    let leq = |a: &str, b: &str| app(app(church_leq(), var(a.to_string())), var(b.to_string()));
+    fun(
+        "m".to_string(),
+        fun("n".to_string(), church_and(leq("m", "n"), leq("n", "m"))),
+    )
+}
+
+// Church Pairs
+pub fn church_pair() -> Box<Expr> {
+    // λx.λy.λf.f x y
+    fun(
+        "x".to_string(),
+        fun(
+            "y".to_string(),
+            fun(
+                "f".to_string(),
+                app(
+                    app(var("f".to_string()), var("x".to_string())),
+                    var("y".to_string()),
+                ),
+            ),
+        ),
+    )
+}
+
+pub fn church_fst() -> Box<Expr> {
+    // λp.p TRUE
+    fun("p".to_string(), app(var("p".to_string()), church_true()))
+}
+
+pub fn church_snd() -> Box<Expr> {
+    // λp.p FALSE
+    fun("p".to_string(), app(var("p".to_string()), church_false()))
+}
+
+// Fixpoint Combinators
+pub fn church_y() -> Box<Expr> {
+    // λf.(λx.f (x x)) (λx.f (x x))
+    let half = || {
+        fun(
+            "x".to_string(),
+            app(
+                var("f".to_string()),
+                app(var("x".to_string()), var("x".to_string())),
+            ),
+        )
+    };
+    fun("f".to_string(), app(half(), half()))
+}
+
+pub fn church_z() -> Box<Expr> {
+    // λf.(λx.f (λv.x x v)) (λx.f (λv.x x v))
+    //
+    // The eta-expanded self-application is a value, so unlike Y this does
+    // not loop under call-by-value.
+    let half = || {
+        fun(
+            "x".to_string(),
+            app(
+                var("f".to_string()),
+                fun(
+                    "v".to_string(),
+                    app(
+                        app(var("x".to_string()), var("x".to_string())),
+                        var("v".to_string()),
+                    ),
+                ),
+            ),
+        )
+    };
+    fun("f".to_string(), app(half(), half()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::eval::{bind_vars, eval};
-
-    /// Helper function to evaluate an expression to normal form
-    fn eval_to_normal(expr: Box<Expr>) -> Expr {
-        let bound = bind_vars(*expr);
-        let mut current = bound;
-        for _ in 0..100 {
-            let next = eval(current.clone());
-            if next == current {
-                return current;
-            }
-            current = next;
-        }
-        current
-    }
-
-    /// Helper to check if two church encodings are structurally equal (ignoring variable IDs)
-    fn structural_eq(expr1: &Expr, expr2: &Expr) -> bool {
-        match (expr1, expr2) {
-            (Expr::Var(v1), Expr::Var(v2)) => v1.name == v2.name,
-            (Expr::Fun(arg1, body1), Expr::Fun(arg2, body2)) => {
-                arg1.name == arg2.name && structural_eq(body1, body2)
-            }
-            (Expr::App(f1, x1), Expr::App(f2, x2)) => {
-                structural_eq(f1, f2) && structural_eq(x1, x2)
-            }
-            _ => false,
-        }
-    }
+    use crate::decode::{decode_bool, decode_nat};
+    use crate::eval::{bind_vars, trace_eval, NameSupply, Strategy};
+    use crate::test_support::eval_to_normal;
 
     #[test]
     fn test_if_true() {
-        let result = eval_to_normal(church_if(
+        let result = eval_to_normal(*church_if(
             church_true(),
             var("x".to_string()),
             var("y".to_string()),
@@ -196,7 +309,7 @@ mod tests {
 
     #[test]
     fn test_if_false() {
-        let result = eval_to_normal(church_if(
+        let result = eval_to_normal(*church_if(
             church_false(),
             var("x".to_string()),
             var("y".to_string()),
@@ -210,114 +323,300 @@ mod tests {
 
     #[test]
     fn test_not_true() {
-        let result = eval_to_normal(church_not(church_true()));
-        let expected = eval_to_normal(church_false());
-        assert!(
-            structural_eq(&result, &expected),
+        let result = eval_to_normal(*church_not(church_true()));
+        assert_eq!(
+            decode_bool(&result),
+            Some(false),
             "NOT TRUE should equal FALSE"
         );
     }
 
     #[test]
     fn test_not_false() {
-        let result = eval_to_normal(church_not(church_false()));
-        let expected = eval_to_normal(church_true());
-        assert!(
-            structural_eq(&result, &expected),
+        let result = eval_to_normal(*church_not(church_false()));
+        assert_eq!(
+            decode_bool(&result),
+            Some(true),
             "NOT FALSE should equal TRUE"
         );
     }
 
     #[test]
     fn test_and_true_true() {
-        let result = eval_to_normal(church_and(church_true(), church_true()));
-        let expected = eval_to_normal(church_true());
-        assert!(
-            structural_eq(&result, &expected),
+        let result = eval_to_normal(*church_and(church_true(), church_true()));
+        assert_eq!(
+            decode_bool(&result),
+            Some(true),
             "AND TRUE TRUE should equal TRUE"
         );
     }
 
     #[test]
     fn test_and_true_false() {
-        let result = eval_to_normal(church_and(church_true(), church_false()));
-        let expected = eval_to_normal(church_false());
-        assert!(
-            structural_eq(&result, &expected),
+        let result = eval_to_normal(*church_and(church_true(), church_false()));
+        assert_eq!(
+            decode_bool(&result),
+            Some(false),
             "AND TRUE FALSE should equal FALSE"
         );
     }
 
     #[test]
     fn test_succ_zero() {
-        let result = eval_to_normal(app(church_succ(), church_zero()));
-        let expected = eval_to_normal(church_one());
-        assert!(structural_eq(&result, &expected), "SUCC 0 should equal 1");
+        let result = eval_to_normal(*app(church_succ(), church_zero()));
+        assert_eq!(decode_nat(&result), Some(1), "SUCC 0 should equal 1");
     }
 
     #[test]
     fn test_succ_two() {
-        let result = eval_to_normal(app(church_succ(), church_two()));
-        let expected = eval_to_normal(church_three());
-        assert!(structural_eq(&result, &expected), "SUCC 2 should equal 3");
+        let result = eval_to_normal(*app(church_succ(), church_numeral(2)));
+        assert_eq!(decode_nat(&result), Some(3), "SUCC 2 should equal 3");
     }
 
     #[test]
     fn test_add_one_two() {
-        let result = eval_to_normal(app(app(church_add(), church_one()), church_two()));
-        let expected = eval_to_normal(church_three());
-        assert!(structural_eq(&result, &expected), "ADD 1 2 should equal 3");
+        let result = eval_to_normal(*app(app(church_add(), church_one()), church_numeral(2)));
+        assert_eq!(decode_nat(&result), Some(3), "ADD 1 2 should equal 3");
     }
 
     #[test]
     fn test_add_zero_two() {
-        let result = eval_to_normal(app(app(church_add(), church_zero()), church_two()));
-        let expected = eval_to_normal(church_two());
-        assert!(structural_eq(&result, &expected), "ADD 0 2 should equal 2");
+        let result = eval_to_normal(*app(app(church_add(), church_zero()), church_numeral(2)));
+        assert_eq!(decode_nat(&result), Some(2), "ADD 0 2 should equal 2");
     }
 
     #[test]
     fn test_mult_two_three() {
-        let result = eval_to_normal(app(app(church_mult(), church_two()), church_three()));
-        // 2 * 3 = 6, we need to construct 6
-        let six = eval_to_normal(app(app(church_add(), church_three()), church_three()));
-        assert!(structural_eq(&result, &six), "MULT 2 3 should equal 6");
+        let result = eval_to_normal(*app(
+            app(church_mult(), church_numeral(2)),
+            church_numeral(3),
+        ));
+        assert_eq!(decode_nat(&result), Some(6), "MULT 2 3 should equal 6");
     }
 
     #[test]
     fn test_mult_zero_three() {
-        let result = eval_to_normal(app(app(church_mult(), church_zero()), church_three()));
-        let expected = eval_to_normal(church_zero());
-        assert!(structural_eq(&result, &expected), "MULT 0 3 should equal 0");
+        let result = eval_to_normal(*app(app(church_mult(), church_zero()), church_numeral(3)));
+        assert_eq!(decode_nat(&result), Some(0), "MULT 0 3 should equal 0");
     }
 
     #[test]
     fn test_is_zero_zero() {
-        let result = eval_to_normal(app(church_is_zero(), church_zero()));
-        let expected = eval_to_normal(church_true());
-        assert!(
-            structural_eq(&result, &expected),
+        let result = eval_to_normal(*app(church_is_zero(), church_zero()));
+        assert_eq!(
+            decode_bool(&result),
+            Some(true),
             "IS_ZERO 0 should equal TRUE"
         );
     }
 
     #[test]
     fn test_is_zero_one() {
-        let result = eval_to_normal(app(church_is_zero(), church_one()));
-        let expected = eval_to_normal(church_false());
-        assert!(
-            structural_eq(&result, &expected),
+        let result = eval_to_normal(*app(church_is_zero(), church_one()));
+        assert_eq!(
+            decode_bool(&result),
+            Some(false),
             "IS_ZERO 1 should equal FALSE"
         );
     }
 
     #[test]
     fn test_is_zero_two() {
-        let result = eval_to_normal(app(church_is_zero(), church_two()));
-        let expected = eval_to_normal(church_false());
-        assert!(
-            structural_eq(&result, &expected),
+        let result = eval_to_normal(*app(church_is_zero(), church_numeral(2)));
+        assert_eq!(
+            decode_bool(&result),
+            Some(false),
             "IS_ZERO 2 should equal FALSE"
         );
     }
+
+    #[test]
+    fn test_or_false_false() {
+        let result = eval_to_normal(*church_or(church_false(), church_false()));
+        assert_eq!(
+            decode_bool(&result),
+            Some(false),
+            "OR FALSE FALSE should equal FALSE"
+        );
+    }
+
+    #[test]
+    fn test_or_false_true() {
+        let result = eval_to_normal(*church_or(church_false(), church_true()));
+        assert_eq!(
+            decode_bool(&result),
+            Some(true),
+            "OR FALSE TRUE should equal TRUE"
+        );
+    }
+
+    #[test]
+    fn test_pred_zero() {
+        let result = eval_to_normal(*app(church_pred(), church_zero()));
+        assert_eq!(decode_nat(&result), Some(0), "PRED 0 should equal 0");
+    }
+
+    #[test]
+    fn test_pred_three() {
+        let result = eval_to_normal(*app(church_pred(), church_numeral(3)));
+        assert_eq!(decode_nat(&result), Some(2), "PRED 3 should equal 2");
+    }
+
+    #[test]
+    fn test_sub_three_one() {
+        let result = eval_to_normal(*app(app(church_sub(), church_numeral(3)), church_one()));
+        assert_eq!(decode_nat(&result), Some(2), "SUB 3 1 should equal 2");
+    }
+
+    #[test]
+    fn test_sub_one_three() {
+        let result = eval_to_normal(*app(app(church_sub(), church_one()), church_numeral(3)));
+        assert_eq!(decode_nat(&result), Some(0), "SUB 1 3 should equal 0");
+    }
+
+    #[test]
+    fn test_numeral_matches_hand_written() {
+        assert!(church_numeral(0).alpha_eq(&church_zero()));
+        assert!(church_numeral(1).alpha_eq(&church_one()));
+    }
+
+    #[test]
+    fn test_xor() {
+        let cases = [
+            (church_false(), church_false(), false),
+            (church_false(), church_true(), true),
+            (church_true(), church_false(), true),
+            (church_true(), church_true(), false),
+        ];
+        for (p, q, expected) in cases {
+            let result = eval_to_normal(*church_xor(p, q));
+            assert_eq!(decode_bool(&result), Some(expected));
+        }
+    }
+
+    #[test]
+    fn test_exp_two_three() {
+        let result = eval_to_normal(*app(
+            app(church_exp(), church_numeral(2)),
+            church_numeral(3),
+        ));
+        assert_eq!(decode_nat(&result), Some(8), "EXP 2 3 should equal 8");
+    }
+
+    #[test]
+    fn test_exp_three_two() {
+        let result = eval_to_normal(*app(
+            app(church_exp(), church_numeral(3)),
+            church_numeral(2),
+        ));
+        assert_eq!(decode_nat(&result), Some(9), "EXP 3 2 should equal 9");
+    }
+
+    #[test]
+    fn test_leq() {
+        let leq = |m, n| {
+            eval_to_normal(*app(
+                app(church_leq(), church_numeral(m)),
+                church_numeral(n),
+            ))
+        };
+        assert_eq!(
+            decode_bool(&leq(2, 3)),
+            Some(true),
+            "LEQ 2 3 should equal TRUE"
+        );
+        assert_eq!(
+            decode_bool(&leq(3, 3)),
+            Some(true),
+            "LEQ 3 3 should equal TRUE"
+        );
+        assert_eq!(
+            decode_bool(&leq(3, 2)),
+            Some(false),
+            "LEQ 3 2 should equal FALSE"
+        );
+    }
+
+    #[test]
+    fn test_eq() {
+        let eq =
+            |m, n| eval_to_normal(*app(app(church_eq(), church_numeral(m)), church_numeral(n)));
+        assert_eq!(
+            decode_bool(&eq(2, 2)),
+            Some(true),
+            "EQ 2 2 should equal TRUE"
+        );
+        assert_eq!(
+            decode_bool(&eq(2, 3)),
+            Some(false),
+            "EQ 2 3 should equal FALSE"
+        );
+        assert_eq!(
+            decode_bool(&eq(3, 2)),
+            Some(false),
+            "EQ 3 2 should equal FALSE"
+        );
+    }
+
+    #[test]
+    fn test_pair_fst_snd() {
+        let pair = || app(app(church_pair(), church_numeral(1)), church_true());
+        let first = eval_to_normal(*app(church_fst(), pair()));
+        assert_eq!(
+            decode_nat(&first),
+            Some(1),
+            "FST (PAIR 1 TRUE) should equal 1"
+        );
+        let second = eval_to_normal(*app(church_snd(), pair()));
+        assert_eq!(
+            decode_bool(&second),
+            Some(true),
+            "SND (PAIR 1 TRUE) should equal TRUE"
+        );
+    }
+
+    /// λr.λn.IS_ZERO n 0 (ADD n (r (PRED n))), the sum 0 + 1 + ... + n
+    fn sum_step() -> Box<Expr> {
+        let n = || var("n".to_string());
+        fun(
+            "r".to_string(),
+            fun(
+                "n".to_string(),
+                church_if(
+                    app(church_is_zero(), n()),
+                    church_zero(),
+                    app(
+                        app(church_add(), n()),
+                        app(var("r".to_string()), app(church_pred(), n())),
+                    ),
+                ),
+            ),
+        )
+    }
+
+    #[test]
+    fn test_y_sum() {
+        let result = eval_to_normal(*app(app(church_y(), sum_step()), church_numeral(3)));
+        assert_eq!(decode_nat(&result), Some(6), "Y SUM 3 should equal 6");
+    }
+
+    #[test]
+    fn test_z_sum() {
+        let result = eval_to_normal(*app(app(church_z(), sum_step()), church_numeral(3)));
+        assert_eq!(decode_nat(&result), Some(6), "Z SUM 3 should equal 6");
+    }
+
+    #[test]
+    fn test_z_terminates_under_call_by_value() {
+        let run = |combinator: Box<Expr>| {
+            let f = fun("r".to_string(), var("r".to_string()));
+            let bound = bind_vars(*app(combinator, f), &mut NameSupply::new());
+            trace_eval(bound, Strategy::CallByValue, 100, false, false).1
+        };
+        assert!(run(church_y()), "Y f should loop under call-by-value");
+        assert!(
+            !run(church_z()),
+            "Z f should reach a value under call-by-value"
+        );
+    }
 }
@@ -0,0 +1,476 @@
+//! Church- and Scott-encoded lists
+//!
+//! A Church list is its own right fold, `λc.λn.c x1 (c x2 n)`, while a Scott
+//! list only exposes its first cell, `λn.λc.c x1 rest`. Only the constructors,
+//! selectors and `fold` differ between the two; the remaining functions are
+//! written in terms of those, so every function takes the encoding to use.
+
+use super::*;
+
+/// How a list is represented as a lambda term
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ListEncoding {
+    /// `λc.λn.c x1 (c x2 n)`, a list is its own right fold
+    #[default]
+    Church,
+    /// `λn.λc.c x1 (λn.λc.c x2 (λn.λc.n))`, a list is a case split on its first cell
+    Scott,
+}
+
+pub fn nil(encoding: ListEncoding) -> Box<Expr> {
+    match encoding {
+        // λc.λn.n
+        ListEncoding::Church => fun("c".to_string(), fun("n".to_string(), var("n".to_string()))),
+        // λn.λc.n
+        ListEncoding::Scott => fun("n".to_string(), fun("c".to_string(), var("n".to_string()))),
+    }
+}
+
+pub fn cons(encoding: ListEncoding) -> Box<Expr> {
+    match encoding {
+        // λh.λt.λc.λn.c h (t c n)
+        ListEncoding::Church => fun(
+            "h".to_string(),
+            fun(
+                "t".to_string(),
+                fun(
+                    "c".to_string(),
+                    fun(
+                        "n".to_string(),
+                        app(
+                            app(var("c".to_string()), var("h".to_string())),
+                            app(
+                                app(var("t".to_string()), var("c".to_string())),
+                                var("n".to_string()),
+                            ),
+                        ),
+                    ),
+                ),
+            ),
+        ),
+        // λh.λt.λn.λc.c h t
+        ListEncoding::Scott => fun(
+            "h".to_string(),
+            fun(
+                "t".to_string(),
+                fun(
+                    "n".to_string(),
+                    fun(
+                        "c".to_string(),
+                        app(
+                            app(var("c".to_string()), var("h".to_string())),
+                            var("t".to_string()),
+                        ),
+                    ),
+                ),
+            ),
+        ),
+    }
+}
+
+/// Build the list holding `items` in order
+pub fn list(encoding: ListEncoding, items: Vec<Box<Expr>>) -> Box<Expr> {
+    items.into_iter().rev().fold(nil(encoding), |rest, item| {
+        app(app(cons(encoding), item), rest)
+    })
+}
+
+/// The first element; the head of the empty list is the empty list
+pub fn head(encoding: ListEncoding) -> Box<Expr> {
+    match encoding {
+        // λl.l (λh.λr.h) NIL
+        ListEncoding::Church => fun(
+            "l".to_string(),
+            app(
+                app(
+                    var("l".to_string()),
+                    fun("h".to_string(), fun("r".to_string(), var("h".to_string()))),
+                ),
+                nil(encoding),
+            ),
+        ),
+        // λl.l NIL (λh.λt.h)
+        ListEncoding::Scott => fun(
+            "l".to_string(),
+            app(
+                app(var("l".to_string()), nil(encoding)),
+                fun("h".to_string(), fun("t".to_string(), var("h".to_string()))),
+            ),
+        ),
+    }
+}
+
+/// Everything but the first element; the tail of the empty list is the empty list
+pub fn tail(encoding: ListEncoding) -> Box<Expr> {
+    match encoding {
+        // λl.FST (l (λh.λp.PAIR (SND p) (CONS h (SND p))) (PAIR NIL NIL))
+        //
+        // Like PRED, this rebuilds the list while remembering the previous
+        // stage, which lags one element behind.
+        ListEncoding::Church => {
+            let snd_p = || app(church_snd(), var("p".to_string()));
+            let step = fun(
+                "h".to_string(),
+                fun(
+                    "p".to_string(),
+                    app(
+                        app(church_pair(), snd_p()),
+                        app(app(cons(encoding), var("h".to_string())), snd_p()),
+                    ),
+                ),
+            );
+            let start = app(app(church_pair(), nil(encoding)), nil(encoding));
+            fun(
+                "l".to_string(),
+                app(church_fst(), app(app(var("l".to_string()), step), start)),
+            )
+        }
+        // λl.l NIL (λh.λt.t)
+        ListEncoding::Scott => fun(
+            "l".to_string(),
+            app(
+                app(var("l".to_string()), nil(encoding)),
+                fun("h".to_string(), fun("t".to_string(), var("t".to_string()))),
+            ),
+        ),
+    }
+}
+
+pub fn is_nil(encoding: ListEncoding) -> Box<Expr> {
+    match encoding {
+        // λl.l (λh.λr.FALSE) TRUE
+        ListEncoding::Church => fun(
+            "l".to_string(),
+            app(
+                app(
+                    var("l".to_string()),
+                    fun("h".to_string(), fun("r".to_string(), church_false())),
+                ),
+                church_true(),
+            ),
+        ),
+        // λl.l TRUE (λh.λt.FALSE)
+        ListEncoding::Scott => fun(
+            "l".to_string(),
+            app(
+                app(var("l".to_string()), church_true()),
+                fun("h".to_string(), fun("t".to_string(), church_false())),
+            ),
+        ),
+    }
+}
+
+/// Right fold, taking the list first: `FOLD [x1, x2] c n = c x1 (c x2 n)`
+pub fn fold(encoding: ListEncoding) -> Box<Expr> {
+    match encoding {
+        // λl.λc.λn.l c n
+        ListEncoding::Church => fun(
+            "l".to_string(),
+            fun(
+                "c".to_string(),
+                fun(
+                    "n".to_string(),
+                    app(
+                        app(var("l".to_string()), var("c".to_string())),
+                        var("n".to_string()),
+                    ),
+                ),
+            ),
+        ),
+        // Y (λf.λl.λc.λn.l n (λh.λt.c h (f t c n)))
+        ListEncoding::Scott => {
+            let recurse = app(
+                app(
+                    app(var("f".to_string()), var("t".to_string())),
+                    var("c".to_string()),
+                ),
+                var("n".to_string()),
+            );
+            let body = app(
+                app(var("l".to_string()), var("n".to_string())),
+                fun(
+                    "h".to_string(),
+                    fun(
+                        "t".to_string(),
+                        app(app(var("c".to_string()), var("h".to_string())), recurse),
+                    ),
+                ),
+            );
+            app(
+                church_y(),
+                fun(
+                    "f".to_string(),
+                    fun(
+                        "l".to_string(),
+                        fun("c".to_string(), fun("n".to_string(), body)),
+                    ),
+                ),
+            )
+        }
+    }
+}
+
+/// `FOLD l c n` with `c` and `n` given as terms
+fn fold_with(encoding: ListEncoding, l: Box<Expr>, c: Box<Expr>, n: Box<Expr>) -> Box<Expr> {
+    app(app(app(fold(encoding), l), c), n)
+}
+
+pub fn map(encoding: ListEncoding) -> Box<Expr> {
+    // λf.λl.FOLD l (λh.λr.CONS (f h) r) NIL
+    let step = fun(
+        "h".to_string(),
+        fun(
+            "r".to_string(),
+            app(
+                app(
+                    cons(encoding),
+                    app(var("f".to_string()), var("h".to_string())),
+                ),
+                var("r".to_string()),
+            ),
+        ),
+    );
+    fun(
+        "f".to_string(),
+        fun(
+            "l".to_string(),
+            fold_with(encoding, var("l".to_string()), step, nil(encoding)),
+        ),
+    )
+}
+
+pub fn filter(encoding: ListEncoding) -> Box<Expr> {
+    // λp.λl.FOLD l (λh.λr.p h (CONS h r) r) NIL
+    let keep = app(
+        app(cons(encoding), var("h".to_string())),
+        var("r".to_string()),
+    );
+    let step = fun(
+        "h".to_string(),
+        fun(
+            "r".to_string(),
+            app(
+                app(app(var("p".to_string()), var("h".to_string())), keep),
+                var("r".to_string()),
+            ),
+        ),
+    );
+    fun(
+        "p".to_string(),
+        fun(
+            "l".to_string(),
+            fold_with(encoding, var("l".to_string()), step, nil(encoding)),
+        ),
+    )
+}
+
+pub fn length(encoding: ListEncoding) -> Box<Expr> {
+    // λl.FOLD l (λh.SUCC) 0
+    fun(
+        "l".to_string(),
+        fold_with(
+            encoding,
+            var("l".to_string()),
+            fun("h".to_string(), church_succ()),
+            church_zero(),
+        ),
+    )
+}
+
+pub fn append(encoding: ListEncoding) -> Box<Expr> {
+    // λa.λb.FOLD a CONS b
+    fun(
+        "a".to_string(),
+        fun(
+            "b".to_string(),
+            fold_with(
+                encoding,
+                var("a".to_string()),
+                cons(encoding),
+                var("b".to_string()),
+            ),
+        ),
+    )
+}
+
+/// The Church numerals `[0, 1, ..., n - 1]`
+pub fn range(encoding: ListEncoding) -> Box<Expr> {
+    // λn.n (λr.λk.CONS k (r (SUCC k))) (λk.NIL) 0
+    let step = fun(
+        "r".to_string(),
+        fun(
+            "k".to_string(),
+            app(
+                app(cons(encoding), var("k".to_string())),
+                app(
+                    var("r".to_string()),
+                    app(church_succ(), var("k".to_string())),
+                ),
+            ),
+        ),
+    );
+    fun(
+        "n".to_string(),
+        app(
+            app(
+                app(var("n".to_string()), step),
+                fun("k".to_string(), nil(encoding)),
+            ),
+            church_zero(),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{decode_bool, decode_list, decode_nat, Value};
+    use crate::test_support::eval_to_normal;
+
+    const ENCODINGS: [ListEncoding; 2] = [ListEncoding::Church, ListEncoding::Scott];
+
+    fn numerals(encoding: ListEncoding, items: &[u64]) -> Box<Expr> {
+        list(encoding, items.iter().map(|&n| church_numeral(n)).collect())
+    }
+
+    fn nats(items: &[u64]) -> Option<Vec<Value>> {
+        Some(items.iter().map(|&n| Value::Nat(n)).collect())
+    }
+
+    #[test]
+    fn test_list_decodes() {
+        for encoding in ENCODINGS {
+            let result = eval_to_normal(*numerals(encoding, &[1, 2, 3]));
+            assert_eq!(decode_list(&result), nats(&[1, 2, 3]), "{:?}", encoding);
+            let result = eval_to_normal(*nil(encoding));
+            assert_eq!(decode_list(&result), nats(&[]), "{:?}", encoding);
+        }
+    }
+
+    #[test]
+    fn test_head_tail() {
+        for encoding in ENCODINGS {
+            let result = eval_to_normal(*app(head(encoding), numerals(encoding, &[1, 2, 3])));
+            assert_eq!(
+                decode_nat(&result),
+                Some(1),
+                "{:?} HEAD [1, 2, 3]",
+                encoding
+            );
+            let result = eval_to_normal(*app(tail(encoding), numerals(encoding, &[1, 2, 3])));
+            assert_eq!(
+                decode_list(&result),
+                nats(&[2, 3]),
+                "{:?} TAIL [1, 2, 3]",
+                encoding
+            );
+            let result = eval_to_normal(*app(tail(encoding), nil(encoding)));
+            assert_eq!(decode_list(&result), nats(&[]), "{:?} TAIL []", encoding);
+        }
+    }
+
+    #[test]
+    fn test_is_nil() {
+        for encoding in ENCODINGS {
+            let result = eval_to_normal(*app(is_nil(encoding), nil(encoding)));
+            assert_eq!(decode_bool(&result), Some(true), "{:?} IS_NIL []", encoding);
+            let result = eval_to_normal(*app(is_nil(encoding), numerals(encoding, &[0])));
+            assert_eq!(
+                decode_bool(&result),
+                Some(false),
+                "{:?} IS_NIL [0]",
+                encoding
+            );
+        }
+    }
+
+    #[test]
+    fn test_fold_sum() {
+        for encoding in ENCODINGS {
+            let result = eval_to_normal(*app(
+                app(
+                    app(fold(encoding), numerals(encoding, &[1, 2, 3])),
+                    church_add(),
+                ),
+                church_zero(),
+            ));
+            assert_eq!(
+                decode_nat(&result),
+                Some(6),
+                "{:?} FOLD [1, 2, 3] ADD 0",
+                encoding
+            );
+        }
+    }
+
+    #[test]
+    fn test_map() {
+        for encoding in ENCODINGS {
+            let result = eval_to_normal(*app(
+                app(map(encoding), church_succ()),
+                numerals(encoding, &[0, 1, 2]),
+            ));
+            assert_eq!(
+                decode_list(&result),
+                nats(&[1, 2, 3]),
+                "{:?} MAP SUCC",
+                encoding
+            );
+        }
+    }
+
+    #[test]
+    fn test_filter() {
+        for encoding in ENCODINGS {
+            let result = eval_to_normal(*app(
+                app(filter(encoding), church_is_zero()),
+                numerals(encoding, &[0, 1, 0, 2]),
+            ));
+            assert_eq!(
+                decode_list(&result),
+                nats(&[0, 0]),
+                "{:?} FILTER IS_ZERO",
+                encoding
+            );
+        }
+    }
+
+    #[test]
+    fn test_length() {
+        for encoding in ENCODINGS {
+            let result = eval_to_normal(*app(length(encoding), numerals(encoding, &[5, 5, 5])));
+            assert_eq!(decode_nat(&result), Some(3), "{:?} LENGTH", encoding);
+        }
+    }
+
+    #[test]
+    fn test_append() {
+        for encoding in ENCODINGS {
+            let result = eval_to_normal(*app(
+                app(append(encoding), numerals(encoding, &[1, 2])),
+                numerals(encoding, &[3]),
+            ));
+            assert_eq!(
+                decode_list(&result),
+                nats(&[1, 2, 3]),
+                "{:?} APPEND",
+                encoding
+            );
+        }
+    }
+
+    #[test]
+    fn test_range() {
+        for encoding in ENCODINGS {
+            let result = eval_to_normal(*app(range(encoding), church_numeral(3)));
+            assert_eq!(
+                decode_list(&result),
+                nats(&[0, 1, 2]),
+                "{:?} RANGE 3",
+                encoding
+            );
+            let result = eval_to_normal(*app(range(encoding), church_zero()));
+            assert_eq!(decode_list(&result), nats(&[]), "{:?} RANGE 0", encoding);
+        }
+    }
+}
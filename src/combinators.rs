@@ -0,0 +1,413 @@
+//! Translation of lambda terms to combinators
+//!
+//! Bracket abstraction removes every binder, leaving applications of
+//! combinators and free variables. The naive translation uses only S, K and
+//! I; Turner's translation adds B, C, S', B' and C', which keeps the result
+//! close to the size of the original term.
+
+use crate::expr::{app, fun, var, Expr};
+
+/// A combinator or an application of combinator terms
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Comb {
+    /// `S f g x = f x (g x)`
+    S,
+    /// `K x y = x`
+    K,
+    /// `I x = x`
+    I,
+    /// `B f g x = f (g x)`
+    B,
+    /// `C f g x = f x g`
+    C,
+    /// `S' c f g x = c (f x) (g x)`
+    SPrime,
+    /// `B' c f g x = c f (g x)`
+    BPrime,
+    /// `C' c f g x = c (f x) g`
+    CPrime,
+    /// A free variable of the translated term
+    Var(String),
+    App(Box<Comb>, Box<Comb>),
+}
+
+/// Which set of bracket abstraction rules to use
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Abstraction {
+    /// `[x]x = I`, `[x]y = K y`, `[x](e1 e2) = S ([x]e1) ([x]e2)`
+    Naive,
+    /// Turner's rules, using K and eta whenever `x` is not free
+    #[default]
+    Turner,
+}
+
+fn ap(f: Comb, x: Comb) -> Comb {
+    Comb::App(Box::new(f), Box::new(x))
+}
+
+fn ap2(f: Comb, x: Comb, y: Comb) -> Comb {
+    ap(ap(f, x), y)
+}
+
+fn ap3(f: Comb, x: Comb, y: Comb, z: Comb) -> Comb {
+    ap(ap2(f, x, y), z)
+}
+
+impl Comb {
+    /// Number of combinators and variables in the term
+    pub fn size(&self) -> usize {
+        match self {
+            Comb::App(f, x) => f.size() + x.size(),
+            _ => 1,
+        }
+    }
+
+    fn mentions(&self, name: &str) -> bool {
+        match self {
+            Comb::Var(v) => v == name,
+            Comb::App(f, x) => f.mentions(name) || x.mentions(name),
+            _ => false,
+        }
+    }
+
+    /// The arguments of `self` if it is `B p q`
+    fn as_b(&self) -> Option<(&Comb, &Comb)> {
+        match self {
+            Comb::App(f, q) => match f.as_ref() {
+                Comb::App(b, p) if **b == Comb::B => Some((p, q)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The argument of `self` if it is `K p`
+    fn as_k(&self) -> Option<&Comb> {
+        match self {
+            Comb::App(k, p) if **k == Comb::K => Some(p),
+            _ => None,
+        }
+    }
+
+    /// Apply a combinator whose arguments are all present
+    fn contract(&self, args: &[&Comb]) -> Comb {
+        let arg = |i: usize| args[i].clone();
+        match self {
+            Comb::I => arg(0),
+            Comb::K => arg(0),
+            Comb::S => ap2(arg(0), arg(2), ap(arg(1), arg(2))),
+            Comb::B => ap(arg(0), ap(arg(1), arg(2))),
+            Comb::C => ap2(arg(0), arg(2), arg(1)),
+            Comb::SPrime => ap2(arg(0), ap(arg(1), arg(3)), ap(arg(2), arg(3))),
+            Comb::BPrime => ap2(arg(0), arg(1), ap(arg(2), arg(3))),
+            Comb::CPrime => ap2(arg(0), ap(arg(1), arg(3)), arg(2)),
+            Comb::Var(_) | Comb::App(_, _) => unreachable!("not a combinator"),
+        }
+    }
+
+    /// Number of arguments the combinator needs before it reduces
+    fn arity(&self) -> Option<usize> {
+        match self {
+            Comb::I => Some(1),
+            Comb::K => Some(2),
+            Comb::S | Comb::B | Comb::C => Some(3),
+            Comb::SPrime | Comb::BPrime | Comb::CPrime => Some(4),
+            Comb::Var(_) | Comb::App(_, _) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Comb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Comb::S => write!(f, "S"),
+            Comb::K => write!(f, "K"),
+            Comb::I => write!(f, "I"),
+            Comb::B => write!(f, "B"),
+            Comb::C => write!(f, "C"),
+            Comb::SPrime => write!(f, "S'"),
+            Comb::BPrime => write!(f, "B'"),
+            Comb::CPrime => write!(f, "C'"),
+            Comb::Var(name) => write!(f, "{}", name),
+            Comb::App(lhs, rhs) => match rhs.as_ref() {
+                Comb::App(_, _) => write!(f, "{} ({})", lhs, rhs),
+                _ => write!(f, "{} {}", lhs, rhs),
+            },
+        }
+    }
+}
+
+/// Remove the variable `x` from `body` with the naive rules
+fn abstract_naive(x: &str, body: Comb) -> Comb {
+    match body {
+        Comb::Var(ref v) if v == x => Comb::I,
+        Comb::App(f, a) => ap2(Comb::S, abstract_naive(x, *f), abstract_naive(x, *a)),
+        _ => ap(Comb::K, body),
+    }
+}
+
+/// Remove the variable `x` from `body` with Turner's rules
+fn abstract_turner(x: &str, body: Comb) -> Comb {
+    match body {
+        Comb::Var(ref v) if v == x => Comb::I,
+        _ if !body.mentions(x) => ap(Comb::K, body),
+        Comb::App(f, a) => {
+            if matches!(a.as_ref(), Comb::Var(v) if v == x) && !f.mentions(x) {
+                return *f;
+            }
+            combine(abstract_turner(x, *f), abstract_turner(x, *a))
+        }
+        _ => unreachable!("only variables and applications mention a variable"),
+    }
+}
+
+/// Simplify `S p q` using Turner's optimizations
+fn combine(p: Comb, q: Comb) -> Comb {
+    match (p.as_k(), q.as_k()) {
+        (Some(p), Some(q)) => return ap(Comb::K, ap(p.clone(), q.clone())),
+        (Some(p), None) if q == Comb::I => return p.clone(),
+        (Some(Comb::App(p1, p2)), None) => {
+            return ap3(Comb::BPrime, *p1.clone(), *p2.clone(), q);
+        }
+        (Some(p), None) => return ap2(Comb::B, p.clone(), q),
+        (None, Some(q)) => {
+            return match p.as_b() {
+                Some((p1, p2)) => ap3(Comb::CPrime, p1.clone(), p2.clone(), q.clone()),
+                None => ap2(Comb::C, p, q.clone()),
+            };
+        }
+        (None, None) => {}
+    }
+    match p.as_b() {
+        Some((p1, p2)) => ap3(Comb::SPrime, p1.clone(), p2.clone(), q),
+        None => ap2(Comb::S, p, q),
+    }
+}
+
+/// Translate a term into combinators
+///
+/// Free variables are kept as [`Comb::Var`]. Returns `None` if the term
+/// contains integer literals or primitives, which have no combinator form.
+pub fn compile(expr: &Expr, abstraction: Abstraction) -> Option<Comb> {
+    match expr {
+        Expr::Var(name) => Some(Comb::Var(name.name.clone())),
+        Expr::Fun(arg, _, body) => {
+            let body = compile(body, abstraction)?;
+            Some(match abstraction {
+                Abstraction::Naive => abstract_naive(&arg.name, body),
+                Abstraction::Turner => abstract_turner(&arg.name, body),
+            })
+        }
+        Expr::App(lhs, rhs) => Some(ap(compile(lhs, abstraction)?, compile(rhs, abstraction)?)),
+        Expr::Lit(_) | Expr::Prim(_) => None,
+    }
+}
+
+/// Contract the leftmost-outermost combinator redex, if there is one
+pub fn step(comb: &Comb) -> Option<Comb> {
+    let mut head = comb;
+    let mut args = Vec::new();
+    while let Comb::App(f, x) = head {
+        args.push(x.as_ref());
+        head = f;
+    }
+    args.reverse();
+
+    if let Some(arity) = head.arity()
+        && args.len() >= arity
+    {
+        return Some(
+            args[arity..]
+                .iter()
+                .fold(head.contract(&args[..arity]), |f, x| ap(f, (*x).clone())),
+        );
+    }
+
+    for (index, arg) in args.iter().enumerate() {
+        if let Some(new_arg) = step(arg) {
+            let mut result = head.clone();
+            for (i, arg) in args.iter().enumerate() {
+                let arg = if i == index {
+                    new_arg.clone()
+                } else {
+                    (*arg).clone()
+                };
+                result = ap(result, arg);
+            }
+            return Some(result);
+        }
+    }
+    None
+}
+
+/// Reduce a combinator term for at most `max_steps` steps
+///
+/// Returns the result and whether the step limit was reached.
+pub fn reduce(comb: Comb, max_steps: usize) -> (Comb, bool) {
+    let mut current = comb;
+    for _ in 0..max_steps {
+        match step(&current) {
+            Some(next) => current = next,
+            None => return (current, false),
+        }
+    }
+    let exceeded = step(&current).is_some();
+    (current, exceeded)
+}
+
+/// Translate a combinator term back into a lambda term
+///
+/// Each combinator becomes its defining abstraction, so the result is
+/// usually not in normal form; evaluate it to get a readable term.
+pub fn decompile(comb: &Comb) -> Expr {
+    let v = |name: &str| var(name.to_string());
+    let lam = |name: &str, body| fun(name.to_string(), body);
+    let term = match comb {
+        Comb::I => lam("x", v("x")),
+        Comb::K => lam("x", lam("y", v("x"))),
+        Comb::S => lam(
+            "f",
+            lam("g", lam("x", app(app(v("f"), v("x")), app(v("g"), v("x"))))),
+        ),
+        Comb::B => lam("f", lam("g", lam("x", app(v("f"), app(v("g"), v("x")))))),
+        Comb::C => lam("f", lam("g", lam("x", app(app(v("f"), v("x")), v("g"))))),
+        Comb::SPrime => lam(
+            "c",
+            lam(
+                "f",
+                lam(
+                    "g",
+                    lam(
+                        "x",
+                        app(app(v("c"), app(v("f"), v("x"))), app(v("g"), v("x"))),
+                    ),
+                ),
+            ),
+        ),
+        Comb::BPrime => lam(
+            "c",
+            lam(
+                "f",
+                lam("g", lam("x", app(app(v("c"), v("f")), app(v("g"), v("x"))))),
+            ),
+        ),
+        Comb::CPrime => lam(
+            "c",
+            lam(
+                "f",
+                lam("g", lam("x", app(app(v("c"), app(v("f"), v("x"))), v("g")))),
+            ),
+        ),
+        Comb::Var(name) => v(name),
+        Comb::App(f, x) => app(Box::new(decompile(f)), Box::new(decompile(x))),
+    };
+    *term
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::church::church_numeral;
+    use crate::decode::decode_nat;
+    use crate::eval::{bind_vars, NameSupply};
+    use crate::parser::parse;
+    use crate::prelude::prelude;
+    use crate::test_support::eval_to_normal;
+
+    fn compile_str(input: &str, abstraction: Abstraction) -> Comb {
+        compile(&parse(input).unwrap(), abstraction).unwrap()
+    }
+
+    #[test]
+    fn test_naive_translation() {
+        assert_eq!(compile_str("\\x.x", Abstraction::Naive).to_string(), "I");
+        assert_eq!(
+            compile_str("\\x.\\y.x", Abstraction::Naive).to_string(),
+            "S (K K) I"
+        );
+        assert_eq!(
+            compile_str("\\x.f x", Abstraction::Naive).to_string(),
+            "S (K f) I"
+        );
+    }
+
+    #[test]
+    fn test_turner_translation() {
+        assert_eq!(
+            compile_str("\\x.\\y.x", Abstraction::Turner).to_string(),
+            "K"
+        );
+        assert_eq!(compile_str("\\x.f x", Abstraction::Turner).to_string(), "f");
+        assert_eq!(
+            compile_str("\\x.\\y.y x", Abstraction::Turner).to_string(),
+            "C I"
+        );
+        assert_eq!(
+            compile_str("\\f.\\g.\\x.f (g x)", Abstraction::Turner).to_string(),
+            "B"
+        );
+        assert_eq!(
+            compile_str("\\x.\\y.\\z.x z (y z)", Abstraction::Turner).to_string(),
+            "S"
+        );
+    }
+
+    #[test]
+    fn test_turner_is_smaller() {
+        for input in ["\\f.\\x.f (f (f x))", "\\m.\\n.\\f.\\x.m f (n f x)"] {
+            let naive = compile_str(input, Abstraction::Naive);
+            let turner = compile_str(input, Abstraction::Turner);
+            assert!(turner.size() < naive.size(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_reducer_rules() {
+        let v = |name: &str| Comb::Var(name.to_string());
+        let cases = [
+            (Comb::SPrime, "c (f x) (g x)"),
+            (Comb::BPrime, "c f (g x)"),
+            (Comb::CPrime, "c (f x) g"),
+        ];
+        for (comb, expected) in cases {
+            let term = ap(ap3(comb, v("c"), v("f"), v("g")), v("x"));
+            assert_eq!(reduce(term, 10).0.to_string(), expected);
+        }
+        // Arguments are reduced once the head is stuck
+        let term = ap(v("f"), ap2(Comb::K, v("a"), v("b")));
+        assert_eq!(reduce(term, 10).0.to_string(), "f a");
+        // S I I (S I I) never reaches a normal form
+        let omega = ap2(Comb::S, Comb::I, Comb::I);
+        assert!(reduce(ap(omega.clone(), omega), 50).1);
+    }
+
+    #[test]
+    fn test_compiled_terms_reduce_like_lambda_terms() {
+        let env = prelude();
+        let mut supply = NameSupply::new();
+        let expr = bind_vars(*parse("ADD 2 3 f x").unwrap(), &mut supply);
+        let expr = env.expand(expr, &mut supply);
+        for abstraction in [Abstraction::Naive, Abstraction::Turner] {
+            let (result, exceeded) = reduce(compile(&expr, abstraction).unwrap(), 10000);
+            assert!(!exceeded);
+            assert_eq!(result.to_string(), "f (f (f (f (f x))))");
+        }
+    }
+
+    #[test]
+    fn test_decompile_round_trip() {
+        for abstraction in [Abstraction::Naive, Abstraction::Turner] {
+            let comb = compile(&church_numeral(3), abstraction).unwrap();
+            assert_eq!(decode_nat(&eval_to_normal(decompile(&comb))), Some(3));
+            let comb = compile_str("\\x.\\y.\\z.y (x z) z", abstraction);
+            assert!(
+                eval_to_normal(decompile(&comb)).alpha_eq(&parse("\\x.\\y.\\z.y (x z) z").unwrap())
+            );
+        }
+    }
+
+    #[test]
+    fn test_primitives_do_not_compile() {
+        assert_eq!(compile(&Expr::Lit(1), Abstraction::Turner), None);
+    }
+}
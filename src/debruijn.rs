@@ -0,0 +1,484 @@
+//! De Bruijn index representation of lambda terms
+//!
+//! Bound variables are replaced by the number of binders between the
+//! occurrence and the binder it refers to, so alpha-equivalent terms are
+//! represented identically and substitution can never capture a name.
+
+use crate::church::{church_false, church_true};
+use crate::eval::{NameSupply, Strategy};
+use crate::expr::{Delta, Expr, PrimOp, VarName};
+use std::collections::HashSet;
+
+/// A lambda term using De Bruijn indices for bound variables
+///
+/// Equality ignores the binder name hints, so `==` is alpha-equivalence.
+#[derive(Clone, Debug)]
+pub enum DbExpr {
+    /// Bound variable, 0 being the innermost enclosing binder
+    Var(usize),
+    /// Free variable, kept by name
+    Free(String),
+    /// Abstraction; the name is only a hint used when converting back to `Expr`
+    Fun(String, Box<DbExpr>),
+    App(Box<DbExpr>, Box<DbExpr>),
+    Lit(i64),
+    Prim(PrimOp),
+}
+
+impl PartialEq for DbExpr {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (DbExpr::Var(i), DbExpr::Var(j)) => i == j,
+            (DbExpr::Free(a), DbExpr::Free(b)) => a == b,
+            (DbExpr::Fun(_, a), DbExpr::Fun(_, b)) => a == b,
+            (DbExpr::App(f1, x1), DbExpr::App(f2, x2)) => f1 == f2 && x1 == x2,
+            (DbExpr::Lit(a), DbExpr::Lit(b)) => a == b,
+            (DbExpr::Prim(a), DbExpr::Prim(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for DbExpr {}
+
+impl DbExpr {
+    /// Convert a named expression, bound or not, to De Bruijn form
+    ///
+    /// Type annotations on binders are dropped.
+    pub fn from_expr(expr: &Expr) -> Self {
+        fn convert(expr: &Expr, scope: &mut Vec<VarName>) -> DbExpr {
+            match expr {
+                Expr::Var(name) => match scope.iter().rev().position(|v| v == name) {
+                    Some(index) => DbExpr::Var(index),
+                    None => DbExpr::Free(name.name.clone()),
+                },
+                Expr::Fun(arg, _, body) => {
+                    scope.push(arg.clone());
+                    let body = convert(body, scope);
+                    scope.pop();
+                    DbExpr::Fun(arg.name.clone(), Box::new(body))
+                }
+                Expr::App(lhs, rhs) => {
+                    DbExpr::App(Box::new(convert(lhs, scope)), Box::new(convert(rhs, scope)))
+                }
+                Expr::Lit(n) => DbExpr::Lit(*n),
+                Expr::Prim(op) => DbExpr::Prim(*op),
+            }
+        }
+        convert(expr, &mut Vec::new())
+    }
+
+    /// Convert back to a named expression with freshly bound IDs
+    ///
+    /// Binder names come from the hints; a hint is primed when it would
+    /// otherwise shadow a name that the body still refers to.
+    pub fn to_expr(&self, supply: &mut NameSupply) -> Expr {
+        fn convert(expr: &DbExpr, scope: &mut Vec<VarName>, supply: &mut NameSupply) -> Expr {
+            match expr {
+                DbExpr::Var(index) => match scope.len().checked_sub(index + 1) {
+                    Some(pos) => Expr::Var(scope[pos].clone()),
+                    None => Expr::Var(VarName::new_free(format!("<{}>", index))),
+                },
+                DbExpr::Free(name) => Expr::Var(VarName::new_free(name.clone())),
+                DbExpr::Fun(hint, body) => {
+                    let mut outer = HashSet::new();
+                    body.outer_names(1, scope, &mut outer);
+                    let mut name = hint.clone();
+                    while outer.contains(&name) {
+                        name.push('\'');
+                    }
+                    scope.push(VarName::new_bound(name, supply.fresh()));
+                    let body = convert(body, scope, supply);
+                    let arg = scope.pop().unwrap();
+                    Expr::Fun(arg, None, Box::new(body))
+                }
+                DbExpr::App(lhs, rhs) => Expr::App(
+                    Box::new(convert(lhs, scope, supply)),
+                    Box::new(convert(rhs, scope, supply)),
+                ),
+                DbExpr::Lit(n) => Expr::Lit(*n),
+                DbExpr::Prim(op) => Expr::Prim(*op),
+            }
+        }
+        convert(self, &mut Vec::new(), supply)
+    }
+
+    /// Collect the names of free variables and of binders outside the term
+    /// (given by `scope`) that are referenced from inside it
+    fn outer_names(&self, depth: usize, scope: &[VarName], names: &mut HashSet<String>) {
+        match self {
+            DbExpr::Var(index) => {
+                if *index >= depth
+                    && let Some(pos) = scope.len().checked_sub(index - depth + 1)
+                {
+                    names.insert(scope[pos].name.clone());
+                }
+            }
+            DbExpr::Free(name) => {
+                names.insert(name.clone());
+            }
+            DbExpr::Fun(_, body) => body.outer_names(depth + 1, scope, names),
+            DbExpr::App(lhs, rhs) => {
+                lhs.outer_names(depth, scope, names);
+                rhs.outer_names(depth, scope, names);
+            }
+            DbExpr::Lit(_) | DbExpr::Prim(_) => {}
+        }
+    }
+
+    /// Add `delta` to every index that points outside the term (at or above `cutoff`)
+    ///
+    /// # Panics
+    ///
+    /// Panics if a negative `delta` would take such an index below zero,
+    /// i.e. if the term refers to one of the binders being removed. Beta
+    /// reduction only shifts down after substituting for index 0, so
+    /// [`DbExpr::step`] and [`DbExpr::normalize`] never panic, whatever the
+    /// indices in the term.
+    pub fn shift(&self, delta: isize, cutoff: usize) -> DbExpr {
+        match self {
+            DbExpr::Var(index) => {
+                if *index >= cutoff {
+                    DbExpr::Var(
+                        index
+                            .checked_add_signed(delta)
+                            .expect("index shifted below zero"),
+                    )
+                } else {
+                    DbExpr::Var(*index)
+                }
+            }
+            DbExpr::Free(name) => DbExpr::Free(name.clone()),
+            DbExpr::Fun(hint, body) => {
+                DbExpr::Fun(hint.clone(), Box::new(body.shift(delta, cutoff + 1)))
+            }
+            DbExpr::App(lhs, rhs) => DbExpr::App(
+                Box::new(lhs.shift(delta, cutoff)),
+                Box::new(rhs.shift(delta, cutoff)),
+            ),
+            DbExpr::Lit(_) | DbExpr::Prim(_) => self.clone(),
+        }
+    }
+
+    /// Replace index `target` with `val`, shifting `val` under each binder
+    pub fn substitute(&self, target: usize, val: &DbExpr) -> DbExpr {
+        match self {
+            DbExpr::Var(index) => {
+                if *index == target {
+                    val.clone()
+                } else {
+                    DbExpr::Var(*index)
+                }
+            }
+            DbExpr::Free(name) => DbExpr::Free(name.clone()),
+            DbExpr::Fun(hint, body) => DbExpr::Fun(
+                hint.clone(),
+                Box::new(body.substitute(target + 1, &val.shift(1, 0))),
+            ),
+            DbExpr::App(lhs, rhs) => DbExpr::App(
+                Box::new(lhs.substitute(target, val)),
+                Box::new(rhs.substitute(target, val)),
+            ),
+            DbExpr::Lit(_) | DbExpr::Prim(_) => self.clone(),
+        }
+    }
+
+    /// Contract the redex `(\.body) val`
+    fn beta(body: &DbExpr, val: &DbExpr) -> DbExpr {
+        body.substitute(0, &val.shift(1, 0)).shift(-1, 0)
+    }
+
+    /// Step a saturated primitive application, like `eval` does for `Expr`
+    fn step_delta(&self, strategy: Strategy) -> Option<DbExpr> {
+        let mut head = self;
+        let mut args = Vec::new();
+        while let DbExpr::App(lhs, rhs) = head {
+            args.push(rhs.as_ref());
+            head = lhs;
+        }
+        args.reverse();
+        let DbExpr::Prim(op) = head else {
+            return None;
+        };
+        if args.len() != op.arity() {
+            return None;
+        }
+        let mut lits = Vec::new();
+        for (i, arg) in args.iter().enumerate().take(op.strict_args()) {
+            if let DbExpr::Lit(n) = arg {
+                lits.push(*n);
+                continue;
+            }
+            let new_arg = arg.step(strategy)?;
+            let mut rebuilt = head.clone();
+            for (j, arg) in args.iter().enumerate() {
+                let arg = if i == j {
+                    new_arg.clone()
+                } else {
+                    (*arg).clone()
+                };
+                rebuilt = DbExpr::App(Box::new(rebuilt), Box::new(arg));
+            }
+            return Some(rebuilt);
+        }
+        match op.delta(&lits)? {
+            Delta::Lit(n) => Some(DbExpr::Lit(n)),
+            Delta::Bool(b) => {
+                let value = if b { church_true() } else { church_false() };
+                Some(DbExpr::from_expr(&value))
+            }
+            Delta::Arg(i) => Some(args[i].clone()),
+        }
+    }
+
+    /// Perform one reduction step, or return `None` if no redex is reachable
+    pub fn step(&self, strategy: Strategy) -> Option<DbExpr> {
+        match self {
+            DbExpr::Var(_) | DbExpr::Free(_) | DbExpr::Lit(_) | DbExpr::Prim(_) => None,
+            DbExpr::Fun(hint, body) => {
+                if !strategy.reduces_under_lambda() {
+                    return None;
+                }
+                body.step(strategy)
+                    .map(|new_body| DbExpr::Fun(hint.clone(), Box::new(new_body)))
+            }
+            DbExpr::App(_, _) if let Some(next) = self.step_delta(strategy) => Some(next),
+            DbExpr::App(lhs, rhs) => match strategy {
+                Strategy::NormalOrder | Strategy::CallByName | Strategy::Head => {
+                    if let DbExpr::Fun(_, body) = lhs.as_ref() {
+                        return Some(DbExpr::beta(body, rhs));
+                    }
+                    if let Some(new_lhs) = lhs.step(strategy) {
+                        return Some(DbExpr::App(Box::new(new_lhs), rhs.clone()));
+                    }
+                    if strategy != Strategy::NormalOrder {
+                        return None;
+                    }
+                    rhs.step(strategy)
+                        .map(|new_rhs| DbExpr::App(lhs.clone(), Box::new(new_rhs)))
+                }
+                Strategy::ApplicativeOrder | Strategy::CallByValue => {
+                    if let Some(new_lhs) = lhs.step(strategy) {
+                        return Some(DbExpr::App(Box::new(new_lhs), rhs.clone()));
+                    }
+                    if let Some(new_rhs) = rhs.step(strategy) {
+                        return Some(DbExpr::App(lhs.clone(), Box::new(new_rhs)));
+                    }
+                    match lhs.as_ref() {
+                        DbExpr::Fun(_, body) => Some(DbExpr::beta(body, rhs)),
+                        _ => None,
+                    }
+                }
+            },
+        }
+    }
+
+    /// Every term reachable by contracting one beta redex, in any position
+    ///
+    /// Redexes are visited leftmost-outermost first; delta rules are not
+    /// included.
+    pub fn contractions(&self) -> Vec<DbExpr> {
+        match self {
+            DbExpr::Var(_) | DbExpr::Free(_) | DbExpr::Lit(_) | DbExpr::Prim(_) => Vec::new(),
+            DbExpr::Fun(hint, body) => body
+                .contractions()
+                .into_iter()
+                .map(|new_body| DbExpr::Fun(hint.clone(), Box::new(new_body)))
+                .collect(),
+            DbExpr::App(lhs, rhs) => {
+                let mut result = Vec::new();
+                if let DbExpr::Fun(_, body) = lhs.as_ref() {
+                    result.push(DbExpr::beta(body, rhs));
+                }
+                for new_lhs in lhs.contractions() {
+                    result.push(DbExpr::App(Box::new(new_lhs), rhs.clone()));
+                }
+                for new_rhs in rhs.contractions() {
+                    result.push(DbExpr::App(lhs.clone(), Box::new(new_rhs)));
+                }
+                result
+            }
+        }
+    }
+
+    /// Reduce for at most `max_steps` steps
+    ///
+    /// Returns the result and whether the step limit was reached.
+    pub fn normalize(&self, strategy: Strategy, max_steps: usize) -> (DbExpr, bool) {
+        let mut current = self.clone();
+        for _ in 0..max_steps {
+            match current.step(strategy) {
+                Some(next) => current = next,
+                None => return (current, false),
+            }
+        }
+        (current, true)
+    }
+}
+
+impl std::fmt::Display for DbExpr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbExpr::Var(index) => write!(f, "{}", index),
+            DbExpr::Free(name) => write!(f, "{}", name),
+            DbExpr::Fun(_, body) => write!(f, "(\\.{})", body),
+            DbExpr::App(lhs, rhs) => write!(f, "({} {})", lhs, rhs),
+            DbExpr::Lit(n) => write!(f, "{}", n),
+            DbExpr::Prim(op) => write!(f, "{}", op),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::bind_vars;
+    use crate::parser::parse;
+
+    fn db(input: &str) -> DbExpr {
+        DbExpr::from_expr(&parse(input).unwrap())
+    }
+
+    #[test]
+    fn test_from_expr_indices() {
+        assert_eq!(db("\\x.\\y.x").to_string(), "(\\.(\\.1))");
+        assert_eq!(db("\\x.\\y.y").to_string(), "(\\.(\\.0))");
+        assert_eq!(db("\\f.\\x.f (f x)").to_string(), "(\\.(\\.(1 (1 0))))");
+    }
+
+    #[test]
+    fn test_free_and_shadowed_variables() {
+        assert_eq!(db("\\x.y x").to_string(), "(\\.(y 0))");
+        assert_eq!(db("\\x.\\x.x").to_string(), "(\\.(\\.0))");
+    }
+
+    #[test]
+    fn test_bound_and_unbound_agree() {
+        let expr = *parse("\\x.\\y.x (\\x.x y)").unwrap();
+        let bound = bind_vars(expr.clone(), &mut NameSupply::new());
+        assert_eq!(DbExpr::from_expr(&expr), DbExpr::from_expr(&bound));
+    }
+
+    #[test]
+    fn test_alpha_equivalent_terms_are_equal() {
+        assert_eq!(db("\\x.\\y.x y"), db("\\a.\\b.a b"));
+        assert_ne!(db("\\x.\\y.x y"), db("\\x.\\y.y x"));
+        assert_ne!(db("\\x.y"), db("\\x.z"));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let term = db("\\f.\\x.f (\\y.f y x)");
+        let expr = term.to_expr(&mut NameSupply::new());
+        assert_eq!(expr.format(false), "(\\f.(\\x.(f (\\y.((f y) x)))))");
+        assert_eq!(DbExpr::from_expr(&expr), term);
+    }
+
+    #[test]
+    fn test_to_expr_avoids_capture() {
+        // λ. y 0 must not print as λy.y y
+        let term = DbExpr::Fun(
+            "y".to_string(),
+            Box::new(DbExpr::App(
+                Box::new(DbExpr::Free("y".to_string())),
+                Box::new(DbExpr::Var(0)),
+            )),
+        );
+        let expr = term.to_expr(&mut NameSupply::new());
+        assert_eq!(expr.format(false), "(\\y'.(y y'))");
+    }
+
+    #[test]
+    fn test_shift() {
+        let term = db("\\x.x z");
+        let open = DbExpr::App(Box::new(DbExpr::Var(0)), Box::new(term));
+        assert_eq!(open.shift(2, 0).to_string(), "(2 (\\.(0 z)))");
+    }
+
+    #[test]
+    fn test_beta_under_binder() {
+        // (λx.λy.x) y  →  λ.y, with y still free
+        let (result, exceeded) = db("(\\x.\\y.x) y").normalize(Strategy::NormalOrder, 10);
+        assert!(!exceeded);
+        assert_eq!(result.to_string(), "(\\.y)");
+    }
+
+    #[test]
+    fn test_duplicated_lambda() {
+        let (result, _) = db("(\\f.f f) (\\x.\\y.x y)").normalize(Strategy::NormalOrder, 10);
+        assert_eq!(result, db("\\a.\\b.a b"));
+    }
+
+    #[test]
+    fn test_self_applied_numeral() {
+        let (result, _) =
+            db("(\\f.\\x.f (f x)) (\\f.\\x.f (f x))").normalize(Strategy::NormalOrder, 20);
+        assert_eq!(result, db("\\f.\\x.f (f (f (f x)))"));
+    }
+
+    #[test]
+    fn test_strategies_agree_with_named_evaluator() {
+        let input = "(\\x.\\y.y) ((\\z.z) w)";
+        let mut supply = NameSupply::new();
+        let bound = bind_vars(*parse(input).unwrap(), &mut supply);
+        for strategy in Strategy::ALL {
+            let named = crate::eval::eval(bound.clone(), strategy, &mut supply);
+            let indexed = db(input).step(strategy).unwrap_or_else(|| db(input));
+            assert_eq!(DbExpr::from_expr(&named), indexed, "strategy {}", strategy);
+        }
+    }
+
+    #[test]
+    fn test_delta_rules() {
+        let options = crate::parser::ParseOptions {
+            primitives: true,
+            ..Default::default()
+        };
+        let term = DbExpr::from_expr(
+            &crate::parser::parse_with("ifz (sub 3 3) (mul 6 7) x", options).unwrap(),
+        );
+        let (result, exceeded) = term.normalize(Strategy::NormalOrder, 10);
+        assert!(!exceeded);
+        assert_eq!(result, DbExpr::Lit(42));
+        assert_eq!(result.to_string(), "42");
+    }
+
+    #[test]
+    fn test_contractions_cover_every_redex() {
+        let results: Vec<String> = db("(\\x.x) ((\\y.y) z)")
+            .contractions()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(results, ["((\\.0) z)", "((\\.0) z)"]);
+        let results: Vec<String> = db("\\a.(\\x.x x) ((\\y.y) a)")
+            .contractions()
+            .iter()
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(
+            results,
+            ["(\\.(((\\.0) 0) ((\\.0) 0)))", "(\\.((\\.(0 0)) 0))"]
+        );
+        assert!(db("\\x.x y").contractions().is_empty());
+    }
+
+    #[test]
+    fn test_dangling_indices() {
+        // (\.3 0) 7 reduces to 2 7, with the dangling index shifted down
+        let term = DbExpr::App(
+            Box::new(DbExpr::Fun(
+                "x".to_string(),
+                Box::new(DbExpr::App(
+                    Box::new(DbExpr::Var(3)),
+                    Box::new(DbExpr::Var(0)),
+                )),
+            )),
+            Box::new(DbExpr::Var(7)),
+        );
+        let (result, _) = term.normalize(Strategy::NormalOrder, 10);
+        assert_eq!(result.to_string(), "(2 7)");
+        let shifted = std::panic::catch_unwind(|| DbExpr::Var(0).shift(-1, 0));
+        assert!(shifted.is_err());
+    }
+}
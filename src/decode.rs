@@ -0,0 +1,459 @@
+//! Readback of normal forms into Rust values
+//!
+//! Recognizers work on the De Bruijn form of a term, so they do not depend on
+//! which names the binders happen to have.
+
+use crate::debruijn::DbExpr;
+use crate::eval::NameSupply;
+use crate::expr::Expr;
+
+/// A decoded normal form
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Nat(u64),
+    Int(i64),
+    Pair(Box<Value>, Box<Value>),
+    List(Vec<Value>),
+    /// A component of a pair or list that is not itself a recognized encoding
+    Term(DbExpr),
+}
+
+impl Value {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_nat(&self) -> Option<u64> {
+        match self {
+            Value::Nat(n) => Some(*n),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Value {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Bool(b) => write!(f, "{}", b),
+            Value::Nat(n) => write!(f, "{}", n),
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Pair(a, b) => write!(f, "({}, {})", a, b),
+            Value::List(items) => {
+                write!(f, "[")?;
+                for (i, item) in items.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            }
+            Value::Term(term) => {
+                write!(f, "{}", term.to_expr(&mut NameSupply::new()).format(false))
+            }
+        }
+    }
+}
+
+type Recognizer = fn(&DbExpr) -> Option<Value>;
+
+/// Every recognized encoding, in the order they are tried
+const RECOGNIZERS: &[(&str, Recognizer)] = &[
+    ("Church numeral", church_nat),
+    ("Scott numeral", scott_nat),
+    ("Parigot numeral", parigot_nat),
+    ("binary numeral", binary_nat),
+    ("Church boolean", church_bool),
+    ("Church list", church_list),
+    ("Scott list", scott_list),
+    ("signed integer", signed_int),
+    ("Church pair", church_pair),
+];
+
+/// Strip exactly `n` leading abstractions
+fn under_binders(term: &DbExpr, n: usize) -> Option<&DbExpr> {
+    let mut body = term;
+    for _ in 0..n {
+        match body {
+            DbExpr::Fun(_, inner) => body = inner,
+            _ => return None,
+        }
+    }
+    Some(body)
+}
+
+/// Check whether any index in `term` refers to one of the `n` binders just outside it
+fn mentions_binders(term: &DbExpr, depth: usize, n: usize) -> bool {
+    match term {
+        DbExpr::Var(index) => *index >= depth && *index < depth + n,
+        DbExpr::Free(_) | DbExpr::Lit(_) | DbExpr::Prim(_) => false,
+        DbExpr::Fun(_, body) => mentions_binders(body, depth + 1, n),
+        DbExpr::App(lhs, rhs) => mentions_binders(lhs, depth, n) || mentions_binders(rhs, depth, n),
+    }
+}
+
+/// Decode a component found under `n` binders of its container
+fn component(term: &DbExpr, n: usize) -> Option<Value> {
+    if mentions_binders(term, 0, n) {
+        return None;
+    }
+    let term = term.shift(-(n as isize), 0);
+    Some(decode_db(&term).unwrap_or(Value::Term(term)))
+}
+
+/// λt.λf.t or λt.λf.f
+fn church_bool(term: &DbExpr) -> Option<Value> {
+    match under_binders(term, 2)? {
+        DbExpr::Var(1) => Some(Value::Bool(true)),
+        DbExpr::Var(0) => Some(Value::Bool(false)),
+        _ => None,
+    }
+}
+
+/// λf.λx.f (f ... (f x))
+fn church_nat(term: &DbExpr) -> Option<Value> {
+    let mut body = under_binders(term, 2)?;
+    let mut n = 0;
+    loop {
+        match body {
+            DbExpr::Var(0) => return Some(Value::Nat(n)),
+            DbExpr::App(f, rest) if matches!(f.as_ref(), DbExpr::Var(1)) => {
+                body = rest;
+                n += 1;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// λs.λz.s (λs.λz.s (... λs.λz.z))
+fn scott_nat(term: &DbExpr) -> Option<Value> {
+    let mut numeral = term.clone();
+    let mut n = 0;
+    loop {
+        match under_binders(&numeral, 2)? {
+            DbExpr::Var(0) => return Some(Value::Nat(n)),
+            DbExpr::App(s, pred) if matches!(s.as_ref(), DbExpr::Var(1)) => {
+                if mentions_binders(pred, 0, 2) {
+                    return None;
+                }
+                numeral = pred.shift(-2, 0);
+                n += 1;
+            }
+            _ => return None,
+        }
+    }
+}
+
+/// λs.λz.s p (p s z), where `p s z` appears already reduced
+fn parigot_nat(term: &DbExpr) -> Option<Value> {
+    let mut numeral = term.clone();
+    let mut n = 0;
+    loop {
+        match under_binders(&numeral, 2)? {
+            DbExpr::Var(0) => return Some(Value::Nat(n)),
+            DbExpr::App(lhs, fold) => match lhs.as_ref() {
+                DbExpr::App(s, pred) if matches!(s.as_ref(), DbExpr::Var(1)) => {
+                    if mentions_binders(pred, 0, 2) {
+                        return None;
+                    }
+                    let pred = pred.shift(-2, 0);
+                    // The predecessor's body is written over the same s and z
+                    if under_binders(&pred, 2)? != fold.as_ref() {
+                        return None;
+                    }
+                    numeral = pred;
+                    n += 1;
+                }
+                _ => return None,
+            },
+            _ => return None,
+        }
+    }
+}
+
+/// λe.λo.λi.o r or λe.λo.λi.i r, down to λe.λo.λi.e
+fn binary_nat(term: &DbExpr) -> Option<Value> {
+    let mut numeral = term.clone();
+    let mut bits = Vec::new();
+    loop {
+        let rest = match under_binders(&numeral, 3)? {
+            DbExpr::Var(2) => break,
+            DbExpr::App(bit, rest) => match bit.as_ref() {
+                DbExpr::Var(1) => {
+                    bits.push(0);
+                    rest
+                }
+                DbExpr::Var(0) => {
+                    bits.push(1);
+                    rest
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+        if mentions_binders(rest, 0, 3) {
+            return None;
+        }
+        numeral = rest.shift(-3, 0);
+    }
+    // Fold from the most significant bit, giving up on overflow
+    let mut n: u64 = 0;
+    for bit in bits.into_iter().rev() {
+        n = n.checked_mul(2)?.checked_add(bit)?;
+    }
+    Some(Value::Nat(n))
+}
+
+/// λf.f a b
+fn church_pair(term: &DbExpr) -> Option<Value> {
+    match under_binders(term, 1)? {
+        DbExpr::App(lhs, second) => match lhs.as_ref() {
+            DbExpr::App(f, first) if matches!(f.as_ref(), DbExpr::Var(0)) => Some(Value::Pair(
+                Box::new(component(first, 1)?),
+                Box::new(component(second, 1)?),
+            )),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// λf.f p n with binary numerals p and n, standing for p - n
+fn signed_int(term: &DbExpr) -> Option<Value> {
+    let (p, n) = match under_binders(term, 1)? {
+        DbExpr::App(lhs, n) => match lhs.as_ref() {
+            DbExpr::App(f, p) if matches!(f.as_ref(), DbExpr::Var(0)) => (p, n),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    let natural = |half: &DbExpr| {
+        if mentions_binders(half, 0, 1) {
+            return None;
+        }
+        i64::try_from(binary_nat(&half.shift(-1, 0))?.as_nat()?).ok()
+    };
+    Some(Value::Int(natural(p)?.checked_sub(natural(n)?)?))
+}
+
+/// λc.λn.c x1 (c x2 (... n))
+fn church_list(term: &DbExpr) -> Option<Value> {
+    let mut body = under_binders(term, 2)?;
+    let mut items = Vec::new();
+    loop {
+        match body {
+            DbExpr::Var(0) => return Some(Value::List(items)),
+            DbExpr::App(lhs, rest) => match lhs.as_ref() {
+                DbExpr::App(c, item) if matches!(c.as_ref(), DbExpr::Var(1)) => {
+                    items.push(component(item, 2)?);
+                    body = rest;
+                }
+                _ => return None,
+            },
+            _ => return None,
+        }
+    }
+}
+
+/// λn.λc.c x1 (λn.λc.c x2 (... λn.λc.n))
+fn scott_list(term: &DbExpr) -> Option<Value> {
+    let mut cell = term.clone();
+    let mut items = Vec::new();
+    loop {
+        match under_binders(&cell, 2)? {
+            DbExpr::Var(1) => return Some(Value::List(items)),
+            DbExpr::App(lhs, rest) => match lhs.as_ref() {
+                DbExpr::App(c, item) if matches!(c.as_ref(), DbExpr::Var(0)) => {
+                    if mentions_binders(rest, 0, 2) {
+                        return None;
+                    }
+                    items.push(component(item, 2)?);
+                    cell = rest.shift(-2, 0);
+                }
+                _ => return None,
+            },
+            _ => return None,
+        }
+    }
+}
+
+fn decode_db(term: &DbExpr) -> Option<Value> {
+    RECOGNIZERS
+        .iter()
+        .find_map(|(_, recognize)| recognize(term))
+}
+
+/// Decode a normal form using the first encoding that recognizes it
+pub fn decode(expr: &Expr) -> Option<Value> {
+    decode_db(&DbExpr::from_expr(expr))
+}
+
+/// Describe every reading of a normal form, e.g. `2 (Church numeral)`
+///
+/// Some terms have several readings: `λx.λy.y` is 0 in every numeral
+/// encoding as well as false, so encodings that agree on the value share it.
+pub fn annotate(expr: &Expr) -> Option<String> {
+    let term = DbExpr::from_expr(expr);
+    let mut readings: Vec<(String, Vec<&str>)> = Vec::new();
+    for (kind, recognize) in RECOGNIZERS {
+        if let Some(value) = recognize(&term) {
+            let value = value.to_string();
+            match readings.iter_mut().find(|(v, _)| *v == value) {
+                Some((_, kinds)) => kinds.push(kind),
+                None => readings.push((value, vec![kind])),
+            }
+        }
+    }
+    if readings.is_empty() {
+        None
+    } else {
+        let readings: Vec<String> = readings
+            .into_iter()
+            .map(|(value, kinds)| format!("{} ({})", value, kinds.join(", ")))
+            .collect();
+        Some(readings.join(" or "))
+    }
+}
+
+pub fn decode_bool(expr: &Expr) -> Option<bool> {
+    church_bool(&DbExpr::from_expr(expr))?.as_bool()
+}
+
+pub fn decode_nat(expr: &Expr) -> Option<u64> {
+    church_nat(&DbExpr::from_expr(expr))?.as_nat()
+}
+
+/// Read back a binary numeral, see [`crate::binary`]
+pub fn decode_binary(expr: &Expr) -> Option<i64> {
+    i64::try_from(binary_nat(&DbExpr::from_expr(expr))?.as_nat()?).ok()
+}
+
+/// Read back a signed integer, see [`crate::signed`]
+pub fn decode_signed(expr: &Expr) -> Option<i64> {
+    match signed_int(&DbExpr::from_expr(expr))? {
+        Value::Int(n) => Some(n),
+        _ => None,
+    }
+}
+
+pub fn decode_pair(expr: &Expr) -> Option<(Value, Value)> {
+    match church_pair(&DbExpr::from_expr(expr))? {
+        Value::Pair(a, b) => Some((*a, *b)),
+        _ => None,
+    }
+}
+
+/// Read back a Church or Scott list
+pub fn decode_list(expr: &Expr) -> Option<Vec<Value>> {
+    let term = DbExpr::from_expr(expr);
+    match church_list(&term).or_else(|| scott_list(&term))? {
+        Value::List(items) => Some(items),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::parse;
+
+    fn term(input: &str) -> Expr {
+        *parse(input).unwrap()
+    }
+
+    #[test]
+    fn test_decode_bool() {
+        assert_eq!(decode_bool(&term("\\a.\\b.a")), Some(true));
+        assert_eq!(decode_bool(&term("\\a.\\b.b")), Some(false));
+        assert_eq!(decode_bool(&term("\\a.\\b.a b")), None);
+    }
+
+    #[test]
+    fn test_decode_nat() {
+        assert_eq!(decode_nat(&term("0")), Some(0));
+        assert_eq!(decode_nat(&term("\\s.\\z.s (s (s z))")), Some(3));
+        assert_eq!(decode_nat(&term("\\f.\\x.x f")), None);
+        assert_eq!(decode_nat(&term("\\f.\\x.f (g x)")), None);
+    }
+
+    #[test]
+    fn test_decode_pair() {
+        let (a, b) = decode_pair(&term("\\p.p 1 (\\t.\\f.t)")).unwrap();
+        assert_eq!(a, Value::Nat(1));
+        assert_eq!(b, Value::Bool(true));
+        // The selector must not appear inside the components
+        assert_eq!(decode_pair(&term("\\p.p p 1")), None);
+    }
+
+    #[test]
+    fn test_decode_list() {
+        let items = decode_list(&term("\\c.\\n.c 1 (c 2 (c 3 n))")).unwrap();
+        assert_eq!(items, vec![Value::Nat(1), Value::Nat(2), Value::Nat(3)]);
+        assert_eq!(decode_list(&term("\\c.\\n.n")), Some(vec![]));
+    }
+
+    #[test]
+    fn test_decode_scott_list() {
+        let items = decode_list(&term("\\n.\\c.c 1 (\\n.\\c.c 2 (\\n.\\c.n))")).unwrap();
+        assert_eq!(items, vec![Value::Nat(1), Value::Nat(2)]);
+        // The tail must not refer to the outer cell's binders
+        assert_eq!(decode_list(&term("\\n.\\c.c 1 (\\a.\\b.n)")), None);
+    }
+
+    #[test]
+    fn test_decode_scott_and_parigot_numerals() {
+        use crate::parigot::parigot_numeral;
+        use crate::scott::scott_numeral;
+
+        assert_eq!(decode(&scott_numeral(3)), Some(Value::Nat(3)));
+        assert_eq!(decode(&parigot_numeral(3)), Some(Value::Nat(3)));
+        // The second half of a Parigot numeral must be its predecessor's fold
+        assert_eq!(
+            parigot_nat(&DbExpr::from_expr(&term("\\s.\\z.s 0 (s z)"))),
+            None
+        );
+        assert_eq!(annotate(&scott_numeral(1)).unwrap(), "1 (Scott numeral)");
+    }
+
+    #[test]
+    fn test_decode_binary_and_signed() {
+        use crate::binary::binary_numeral;
+        use crate::signed::signed_numeral;
+
+        assert_eq!(decode_binary(&binary_numeral(1234)), Some(1234));
+        assert_eq!(
+            decode_binary(&term("\\e.\\o.\\i.o (\\e.\\o.\\i.e)")),
+            Some(0)
+        );
+        assert_eq!(decode_signed(&signed_numeral(-42)), Some(-42));
+        assert_eq!(
+            annotate(&signed_numeral(-1)).unwrap(),
+            "-1 (signed integer) or (0, 1) (Church pair)"
+        );
+    }
+
+    #[test]
+    fn test_display_nested_values() {
+        let value = decode(&term("\\c.\\n.c (\\p.p 1 2) (c x n)")).unwrap();
+        assert_eq!(value.to_string(), "[(1, 2), x]");
+    }
+
+    #[test]
+    fn test_annotate() {
+        assert_eq!(annotate(&term("2")).unwrap(), "2 (Church numeral)");
+        assert_eq!(
+            annotate(&term("\\x.\\y.x")).unwrap(),
+            "true (Church boolean) or [] (Scott list)"
+        );
+        assert_eq!(
+            annotate(&term("\\x.\\y.y")).unwrap(),
+            "0 (Church numeral, Scott numeral, Parigot numeral) or false (Church boolean) or [] (Church list)"
+        );
+        assert_eq!(annotate(&term("\\x.x")), None);
+    }
+}
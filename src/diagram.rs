@@ -0,0 +1,315 @@
+//! John Tromp's lambda diagrams
+//!
+//! An abstraction is a horizontal line, with its body drawn below it. A
+//! variable is a vertical line hanging from the line of the abstraction that
+//! binds it, or from the top edge when it is free. An application joins the
+//! leftmost lines of its two sides with a horizontal line underneath both,
+//! and the function's line carries on below. Diagrams are laid out on a grid
+//! of unit squares and drawn as SVG or as block characters for the terminal.
+
+use crate::eval::Reducer;
+use crate::expr::{Expr, VarName};
+
+/// Size in pixels of a grid square in the REPL's SVG output
+pub const DEFAULT_SCALE: usize = 8;
+
+/// Seconds each frame is shown in the REPL's animations
+pub const DEFAULT_FRAME_SECONDS: f64 = 0.5;
+
+/// A horizontal or vertical line between two grid squares, inclusive
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Line {
+    x1: usize,
+    y1: usize,
+    x2: usize,
+    y2: usize,
+}
+
+/// The lines of a lambda diagram and the size of the grid they fill
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagram {
+    pub width: usize,
+    pub height: usize,
+    lines: Vec<Line>,
+}
+
+/// Where a subterm ended up: its width, its lowest row and the line of its
+/// head variable, which the enclosing term extends downwards
+struct Layout {
+    width: usize,
+    bottom: usize,
+    head: usize,
+}
+
+fn layout<'a>(
+    expr: &'a Expr,
+    scope: &mut Vec<(&'a VarName, usize)>,
+    x: usize,
+    top: usize,
+    lines: &mut Vec<Line>,
+) -> Option<Layout> {
+    match expr {
+        Expr::Var(name) => {
+            let from = scope
+                .iter()
+                .rev()
+                .find(|(bound, _)| *bound == name)
+                .map_or(0, |(_, row)| *row);
+            lines.push(Line {
+                x1: x + 1,
+                y1: from,
+                x2: x + 1,
+                y2: top,
+            });
+            Some(Layout {
+                width: 3,
+                bottom: top,
+                head: lines.len() - 1,
+            })
+        }
+        Expr::Fun(arg, _, body) => {
+            let line = lines.len();
+            lines.push(Line {
+                x1: x,
+                y1: top,
+                x2: x,
+                y2: top,
+            });
+            scope.push((arg, top));
+            let body = layout(body, scope, x, top + 2, lines);
+            scope.pop();
+            let body = body?;
+            lines[line].x2 = x + body.width - 1;
+            Some(body)
+        }
+        Expr::App(lhs, rhs) => {
+            let lhs = layout(lhs, scope, x, top, lines)?;
+            let rhs = layout(rhs, scope, x + lhs.width + 1, top, lines)?;
+            let bottom = lhs.bottom.max(rhs.bottom) + 2;
+            lines[lhs.head].y2 = bottom;
+            lines[rhs.head].y2 = bottom;
+            lines.push(Line {
+                x1: lines[lhs.head].x1,
+                y1: bottom,
+                x2: lines[rhs.head].x1,
+                y2: bottom,
+            });
+            Some(Layout {
+                width: lhs.width + 1 + rhs.width,
+                bottom,
+                head: lhs.head,
+            })
+        }
+        Expr::Lit(_) | Expr::Prim(_) => None,
+    }
+}
+
+impl Diagram {
+    /// Lay out the diagram of a term, or `None` if it contains primitives
+    pub fn new(expr: &Expr) -> Option<Diagram> {
+        let mut lines = Vec::new();
+        let root = layout(expr, &mut Vec::new(), 0, 0, &mut lines)?;
+        // The head line sticks out below the diagram
+        lines[root.head].y2 = root.bottom + 2;
+        Some(Diagram {
+            width: root.width,
+            height: root.bottom + 3,
+            lines,
+        })
+    }
+
+    fn rects(&self, out: &mut String) {
+        for line in &self.lines {
+            out.push_str(&format!(
+                "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"/>",
+                line.x1,
+                line.y1,
+                line.x2 - line.x1 + 1,
+                line.y2 - line.y1 + 1
+            ));
+        }
+    }
+
+    /// Render as an SVG image, with each grid square `scale` pixels wide
+    pub fn to_svg(&self, scale: usize) -> String {
+        let mut out = svg_header(self.width, self.height, scale);
+        out.push_str("<g>");
+        self.rects(&mut out);
+        out.push_str("</g>\n</svg>\n");
+        out
+    }
+
+    /// Render with block characters, two grid rows to a line of text
+    pub fn to_blocks(&self) -> String {
+        let mut grid = vec![vec![false; self.width]; self.height + 1];
+        for line in &self.lines {
+            for row in &mut grid[line.y1..=line.y2] {
+                for cell in &mut row[line.x1..=line.x2] {
+                    *cell = true;
+                }
+            }
+        }
+        let mut out = String::new();
+        for rows in grid.chunks(2) {
+            let text: String = rows[0]
+                .iter()
+                .zip(&rows[1])
+                .map(|(upper, lower)| match (upper, lower) {
+                    (true, true) => '█',
+                    (true, false) => '▀',
+                    (false, true) => '▄',
+                    (false, false) => ' ',
+                })
+                .collect();
+            out.push_str(text.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+fn svg_header(width: usize, height: usize, scale: usize) -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"0 0 {} {}\" \
+         width=\"{}\" height=\"{}\" shape-rendering=\"crispEdges\">\n\
+         <rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n",
+        width,
+        height,
+        width * scale,
+        height * scale
+    )
+}
+
+/// Render the diagrams as the frames of an animated SVG, looping forever
+///
+/// Each frame is shown for `seconds` seconds, aligned to the top left of the
+/// largest one. Viewers without animation support show the first frame.
+pub fn animation(frames: &[Diagram], scale: usize, seconds: f64) -> String {
+    let width = frames.iter().map(|d| d.width).max().unwrap_or(0);
+    let height = frames.iter().map(|d| d.height).max().unwrap_or(0);
+    let mut out = svg_header(width, height, scale);
+    let n = frames.len();
+    for (i, frame) in frames.iter().enumerate() {
+        let mut keys = Vec::new();
+        if i > 0 {
+            keys.push(("hidden", 0.0));
+        }
+        keys.push(("visible", i as f64 / n as f64));
+        if i + 1 < n {
+            keys.push(("hidden", (i + 1) as f64 / n as f64));
+        }
+        let values: Vec<&str> = keys.iter().map(|(value, _)| *value).collect();
+        let times: Vec<String> = keys
+            .iter()
+            .map(|(_, time)| format!("{:.4}", time))
+            .collect();
+        out.push_str(&format!(
+            "<g visibility=\"{}\">\
+             <animate attributeName=\"visibility\" values=\"{}\" keyTimes=\"{}\" \
+             calcMode=\"discrete\" dur=\"{}s\" repeatCount=\"indefinite\"/>",
+            if i == 0 { "visible" } else { "hidden" },
+            values.join(";"),
+            times.join(";"),
+            seconds * n as f64
+        ));
+        frame.rects(&mut out);
+        out.push_str("</g>\n");
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Diagrams of a term and of each step of its reduction, at most
+/// `max_steps` of them
+///
+/// Returns `None` if a term along the way contains primitives.
+pub fn frames(expr: Expr, reducer: &Reducer, max_steps: usize) -> Option<Vec<Diagram>> {
+    let mut frames = vec![Diagram::new(&expr)?];
+    let mut steps = reducer.steps(expr);
+    while frames.len() <= max_steps {
+        let Some(step) = steps.next() else {
+            break;
+        };
+        step.expect("evaluation without builtins cannot fail");
+        frames.push(Diagram::new(steps.term())?);
+    }
+    Some(frames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::{bind_vars, NameSupply, Strategy};
+    use crate::parser::{parse, parse_with, ParseOptions};
+
+    fn diagram(input: &str) -> Diagram {
+        let expr = bind_vars(*parse(input).unwrap(), &mut NameSupply::new());
+        Diagram::new(&expr).unwrap()
+    }
+
+    #[test]
+    fn test_identity() {
+        let d = diagram("\\x.x");
+        assert_eq!((d.width, d.height), (3, 5));
+        assert_eq!(d.to_blocks(), "▀█▀\n █\n ▀\n");
+    }
+
+    #[test]
+    fn test_church_two() {
+        let d = diagram("\\f.\\x.f (f x)");
+        assert_eq!((d.width, d.height), (11, 11));
+        assert_eq!(
+            d.to_blocks(),
+            "▀█▀▀▀█▀▀▀▀▀\n\
+             ▀█▀▀▀█▀▀▀█▀\n \
+             █   █   █\n \
+             █   █▀▀▀▀\n \
+             █▀▀▀▀\n \
+             ▀\n"
+        );
+    }
+
+    #[test]
+    fn test_variables_hang_from_their_binder() {
+        // \x.\y.x and \x.\y.y differ only in where the line starts
+        let k = diagram("\\x.\\y.x");
+        let ki = diagram("\\x.\\y.y");
+        assert_eq!(k.to_blocks(), "▀█▀\n▀█▀\n █\n ▀\n");
+        assert_eq!(ki.to_blocks(), "▀▀▀\n▀█▀\n █\n ▀\n");
+        // A free variable starts at the top edge
+        assert_eq!(diagram("y").to_blocks(), " █\n ▀\n");
+    }
+
+    #[test]
+    fn test_svg() {
+        let svg = diagram("\\x.x").to_svg(10);
+        assert!(svg.contains("viewBox=\"0 0 3 5\" width=\"30\" height=\"50\""));
+        assert!(svg.contains("<rect x=\"0\" y=\"0\" width=\"3\" height=\"1\"/>"));
+        assert!(svg.contains("<rect x=\"1\" y=\"0\" width=\"1\" height=\"5\"/>"));
+    }
+
+    #[test]
+    fn test_primitives_have_no_diagram() {
+        let options = ParseOptions {
+            primitives: true,
+            ..Default::default()
+        };
+        assert_eq!(Diagram::new(&parse_with("add 1", options).unwrap()), None);
+    }
+
+    #[test]
+    fn test_animation_frames() {
+        let expr = bind_vars(
+            *parse("(\\x.x) ((\\y.y) (\\z.z))").unwrap(),
+            &mut NameSupply::new(),
+        );
+        let frames = frames(expr, &Reducer::new(Strategy::NormalOrder, false), 10).unwrap();
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[2], diagram("\\z.z"));
+        let svg = animation(&frames, 4, 0.5);
+        assert_eq!(svg.matches("<g ").count(), 3);
+        assert!(svg.contains("values=\"visible;hidden\" keyTimes=\"0.0000;0.3333\""));
+        assert!(svg.contains("values=\"hidden;visible\" keyTimes=\"0.0000;0.6667\""));
+        assert!(svg.contains("dur=\"1.5s\""));
+    }
+}
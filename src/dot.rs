@@ -0,0 +1,254 @@
+//! Graphviz DOT export
+//!
+//! [`Expr::to_dot`] draws a term's syntax tree, with a dashed edge from each
+//! variable occurrence back to the abstraction that binds it.
+//! [`reduction_graph`] collects the terms reachable from a start term by beta
+//! steps in any position, identified up to alpha-equivalence, and
+//! [`ReductionGraph::to_dot`] draws them with each edge labelled by the
+//! strategies that take that step.
+
+use crate::debruijn::DbExpr;
+use crate::eval::{NameSupply, Strategy};
+use crate::expr::{Expr, VarName};
+
+/// Term limit used by the REPL's `:graph`
+pub const DEFAULT_TERMS: usize = 100;
+
+/// Escape a label for use inside a double-quoted DOT string
+fn escape(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+impl Expr {
+    /// Render the syntax tree in Graphviz DOT format
+    ///
+    /// Abstractions are labelled `λx`, applications `@`, and free variables,
+    /// literals and primitives are drawn as boxes.
+    pub fn to_dot(&self) -> String {
+        fn node<'a>(
+            expr: &'a Expr,
+            scope: &mut Vec<(&'a VarName, usize)>,
+            next: &mut usize,
+            out: &mut String,
+        ) -> usize {
+            let id = *next;
+            *next += 1;
+            match expr {
+                Expr::Var(name) => match scope.iter().rev().find(|(bound, _)| *bound == name) {
+                    Some((_, binder)) => {
+                        out.push_str(&format!(
+                            "    n{} [label=\"{}\"];\n",
+                            id,
+                            escape(&name.name)
+                        ));
+                        out.push_str(&format!(
+                            "    n{} -> n{} [style=dashed, color=gray, constraint=false];\n",
+                            id, binder
+                        ));
+                    }
+                    None => out.push_str(&format!(
+                        "    n{} [label=\"{}\", shape=box];\n",
+                        id,
+                        escape(&name.name)
+                    )),
+                },
+                Expr::Fun(arg, ty, body) => {
+                    let label = match ty {
+                        Some(ty) => format!("λ{}:{}", arg.name, ty),
+                        None => format!("λ{}", arg.name),
+                    };
+                    out.push_str(&format!("    n{} [label=\"{}\"];\n", id, escape(&label)));
+                    scope.push((arg, id));
+                    let child = node(body, scope, next, out);
+                    scope.pop();
+                    out.push_str(&format!("    n{} -> n{};\n", id, child));
+                }
+                Expr::App(lhs, rhs) => {
+                    out.push_str(&format!("    n{} [label=\"@\"];\n", id));
+                    let lhs = node(lhs, scope, next, out);
+                    let rhs = node(rhs, scope, next, out);
+                    out.push_str(&format!("    n{} -> n{};\n", id, lhs));
+                    out.push_str(&format!("    n{} -> n{};\n", id, rhs));
+                }
+                Expr::Lit(n) => {
+                    out.push_str(&format!("    n{} [label=\"{}\", shape=box];\n", id, n))
+                }
+                Expr::Prim(op) => {
+                    out.push_str(&format!("    n{} [label=\"{}\", shape=box];\n", id, op))
+                }
+            }
+            id
+        }
+
+        let mut out = String::from("digraph expr {\n    node [shape=ellipse];\n");
+        node(self, &mut Vec::new(), &mut 0, &mut out);
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Terms reachable from a start term and the beta steps between them
+#[derive(Clone, Debug)]
+pub struct ReductionGraph {
+    /// Reached terms, the start term first
+    pub terms: Vec<DbExpr>,
+    /// Steps as `(from, to, strategies)`, indexing into `terms`; `strategies`
+    /// lists the strategies whose next step from `from` is this one
+    pub edges: Vec<(usize, usize, Vec<Strategy>)>,
+    /// Whether every reachable term fitted within the term limit
+    pub complete: bool,
+}
+
+/// Explore every beta step from `expr`, keeping at most `max_terms` terms
+///
+/// Steps taken by a strategy are included too, so delta rules appear when
+/// primitives are used. Steps to terms beyond the limit are dropped and
+/// `complete` is cleared.
+pub fn reduction_graph(expr: &Expr, max_terms: usize) -> ReductionGraph {
+    let mut graph = ReductionGraph {
+        terms: vec![DbExpr::from_expr(expr)],
+        edges: Vec::new(),
+        complete: true,
+    };
+    let mut index = 0;
+    while index < graph.terms.len() {
+        let term = graph.terms[index].clone();
+        let mut targets: Vec<(DbExpr, Vec<Strategy>)> = Vec::new();
+        for next in term.contractions() {
+            if !targets.iter().any(|(target, _)| *target == next) {
+                targets.push((next, Vec::new()));
+            }
+        }
+        for strategy in Strategy::ALL {
+            let Some(next) = term.step(strategy) else {
+                continue;
+            };
+            match targets.iter_mut().find(|(target, _)| *target == next) {
+                Some((_, strategies)) => strategies.push(strategy),
+                None => targets.push((next, vec![strategy])),
+            }
+        }
+        for (target, strategies) in targets {
+            let to = match graph.terms.iter().position(|term| *term == target) {
+                Some(to) => to,
+                None if graph.terms.len() < max_terms => {
+                    graph.terms.push(target);
+                    graph.terms.len() - 1
+                }
+                None => {
+                    graph.complete = false;
+                    continue;
+                }
+            };
+            graph.edges.push((index, to, strategies));
+        }
+        index += 1;
+    }
+    graph
+}
+
+impl ReductionGraph {
+    /// Render the graph in Graphviz DOT format
+    ///
+    /// The start term is drawn bold and normal forms with a double border.
+    /// Steps that no strategy takes are dotted.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph reduction {\n    node [shape=box];\n");
+        for (i, term) in self.terms.iter().enumerate() {
+            let label = term.to_expr(&mut NameSupply::new()).format(false);
+            let mut attrs = format!("label=\"{}\"", escape(&label));
+            if i == 0 {
+                attrs.push_str(", style=bold");
+            }
+            if term.step(Strategy::NormalOrder).is_none() {
+                attrs.push_str(", peripheries=2");
+            }
+            out.push_str(&format!("    n{} [{}];\n", i, attrs));
+        }
+        for (from, to, strategies) in &self.edges {
+            if strategies.is_empty() {
+                out.push_str(&format!("    n{} -> n{} [style=dotted];\n", from, to));
+            } else {
+                let names: Vec<&str> = strategies.iter().map(|s| s.name()).collect();
+                out.push_str(&format!(
+                    "    n{} -> n{} [label=\"{}\"];\n",
+                    from,
+                    to,
+                    names.join(", ")
+                ));
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::bind_vars;
+    use crate::parser::parse;
+
+    fn graph(input: &str, max_terms: usize) -> ReductionGraph {
+        reduction_graph(&parse(input).unwrap(), max_terms)
+    }
+
+    #[test]
+    fn test_syntax_tree() {
+        let expr = bind_vars(*parse("\\x.x y").unwrap(), &mut NameSupply::new());
+        assert_eq!(
+            expr.to_dot(),
+            "digraph expr {
+    node [shape=ellipse];
+    n0 [label=\"λx\"];
+    n1 [label=\"@\"];
+    n2 [label=\"x\"];
+    n2 -> n0 [style=dashed, color=gray, constraint=false];
+    n3 [label=\"y\", shape=box];
+    n1 -> n2;
+    n1 -> n3;
+    n0 -> n1;
+}
+"
+        );
+    }
+
+    #[test]
+    fn test_binder_edges_follow_shadowing() {
+        let dot = parse("\\x.\\x.x").unwrap().to_dot();
+        assert!(dot.contains("n2 -> n1 [style=dashed"));
+        assert!(!dot.contains("n2 -> n0"));
+    }
+
+    #[test]
+    fn test_diamond() {
+        // Both redexes lead to the same normal form, so the graph is a diamond
+        let g = graph("(\\x.x) ((\\y.y) z)", 10);
+        assert!(g.complete);
+        assert_eq!(g.terms.len(), 3);
+        assert_eq!(g.terms[2].to_string(), "z");
+        let mut edges: Vec<(usize, usize)> = g.edges.iter().map(|(f, t, _)| (*f, *t)).collect();
+        edges.sort();
+        assert_eq!(edges, [(0, 1), (1, 2)]);
+        let (_, _, strategies) = &g.edges[0];
+        assert_eq!(strategies.len(), Strategy::ALL.len());
+    }
+
+    #[test]
+    fn test_strategies_label_their_steps() {
+        let g = graph("(\\x.\\y.y) ((\\z.z z) (\\z.z z))", 10);
+        assert!(g.complete);
+        let dot = g.to_dot();
+        assert!(dot.contains("n0 -> n1 [label=\"normal, cbn, head\"]"));
+        assert!(dot.contains("n0 -> n0 [label=\"applicative, cbv\"]"));
+        assert!(dot.contains("n1 [label=\"(\\\\y.y)\", peripheries=2]"));
+    }
+
+    #[test]
+    fn test_term_limit() {
+        // Each step of this term grows it, so it has no finite graph
+        let g = graph("(\\x.x x x) (\\x.x x x)", 5);
+        assert!(!g.complete);
+        assert_eq!(g.terms.len(), 5);
+    }
+}
@@ -0,0 +1,201 @@
+//! Named definitions that later expressions can refer to
+
+use crate::eval::{free_names, replace_all, NameSupply};
+use crate::expr::{Expr, VarName};
+use crate::types::Type;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// A set of top-level definitions, such as the ones made in the REPL
+///
+/// Definitions are expanded when they are stored, so each one is a snapshot:
+/// redefining or removing a name later does not change terms that were
+/// defined in terms of it. Definitions made from System F terms also keep
+/// their type, which goes away with the definition.
+#[derive(Clone, Debug, Default)]
+pub struct Env {
+    defs: BTreeMap<String, Definition>,
+}
+
+#[derive(Clone, Debug)]
+struct Definition {
+    expr: Expr,
+    /// Type of the System F term the definition was erased from
+    system_f: Option<Type>,
+}
+
+impl Env {
+    pub fn new() -> Self {
+        Env {
+            defs: BTreeMap::new(),
+        }
+    }
+
+    /// Expand `expr` against the current definitions and store it under `name`
+    pub fn define(&mut self, name: String, expr: Expr) {
+        self.insert(name, expr, None);
+    }
+
+    /// Like [`Env::define`], for the erasure of a System F term of type `ty`
+    pub fn define_typed(&mut self, name: String, expr: Expr, ty: Type) {
+        self.insert(name, expr, Some(ty));
+    }
+
+    fn insert(&mut self, name: String, expr: Expr, system_f: Option<Type>) {
+        let mut supply = NameSupply::above(&expr);
+        let expr = self.expand(expr, &mut supply);
+        self.defs.insert(name, Definition { expr, system_f });
+    }
+
+    /// Remove a definition, returning whether it existed
+    pub fn undefine(&mut self, name: &str) -> bool {
+        self.defs.remove(name).is_some()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Expr> {
+        self.defs.get(name).map(|def| &def.expr)
+    }
+
+    /// System F types of the definitions that were made with one
+    pub fn system_f_types(&self) -> HashMap<String, Type> {
+        self.defs
+            .iter()
+            .filter_map(|(name, def)| Some((name.clone(), def.system_f.clone()?)))
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.defs.is_empty()
+    }
+
+    /// Iterate over the definitions in name order
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Expr)> {
+        self.defs.iter().map(|(name, def)| (name, &def.expr))
+    }
+
+    /// Replace every free variable that names a definition with a copy of it
+    ///
+    /// `expr` should already be bound; binders with the same name as a
+    /// definition shadow it. Each copy gets fresh IDs from `supply`. All
+    /// names are replaced in one pass, so a name that is still free inside a
+    /// definition stays free in the copy.
+    pub fn expand(&self, expr: Expr, supply: &mut NameSupply) -> Expr {
+        let mut names = HashSet::new();
+        free_names(&expr, &mut Vec::new(), &mut names);
+
+        let vars: Vec<(VarName, &Expr)> = names
+            .into_iter()
+            .filter_map(|name| {
+                let def = self.get(&name)?;
+                Some((VarName::new_free(name), def))
+            })
+            .collect();
+        let vals: Vec<(&VarName, &Expr)> = vars.iter().map(|(var, def)| (var, *def)).collect();
+        replace_all(expr, &vals, supply)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::{bind_vars, trace_eval, Strategy};
+    use crate::parser::parse;
+
+    fn bound(input: &str, supply: &mut NameSupply) -> Expr {
+        bind_vars(*parse(input).unwrap(), supply)
+    }
+
+    fn env_with(defs: &[(&str, &str)]) -> Env {
+        let mut env = Env::new();
+        for (name, input) in defs {
+            let expr = bound(input, &mut NameSupply::new());
+            env.define(name.to_string(), expr);
+        }
+        env
+    }
+
+    #[test]
+    fn test_expand_and_evaluate() {
+        let env = env_with(&[
+            ("TWO", "\\f.\\x.f (f x)"),
+            ("SUCC", "\\n.\\f.\\x.f (n f x)"),
+        ]);
+        let mut supply = NameSupply::new();
+        let expr = env.expand(bound("SUCC TWO", &mut supply), &mut supply);
+        let (result, _) = trace_eval(expr, Strategy::NormalOrder, 100, false, false);
+        assert!(result.alpha_eq(&bound("\\f.\\x.f (f (f x))", &mut supply)));
+    }
+
+    #[test]
+    fn test_definitions_refer_to_earlier_ones() {
+        let env = env_with(&[("ID", "\\x.x"), ("K", "\\a.\\b.ID a")]);
+        let mut supply = NameSupply::new();
+        let expected = bound("\\a.\\b.(\\x.x) a", &mut supply);
+        assert!(env.get("K").unwrap().alpha_eq(&expected));
+    }
+
+    #[test]
+    fn test_bound_variables_shadow_definitions() {
+        let env = env_with(&[("x", "\\y.y")]);
+        let mut supply = NameSupply::new();
+        let expr = bound("\\x.x", &mut supply);
+        assert_eq!(env.expand(expr.clone(), &mut supply), expr);
+    }
+
+    #[test]
+    fn test_expansion_avoids_capture() {
+        // The free y in the definition must not be captured by λy
+        let env = env_with(&[("FOO", "y")]);
+        let mut supply = NameSupply::new();
+        let expr = env.expand(bound("\\y.FOO", &mut supply), &mut supply);
+        assert_eq!(expr.format(false), "(\\y'.y)");
+    }
+
+    #[test]
+    fn test_copies_get_fresh_ids() {
+        let env = env_with(&[("ID", "\\x.x")]);
+        let mut supply = NameSupply::new();
+        let expr = env.expand(bound("ID ID", &mut supply), &mut supply);
+        match expr {
+            Expr::App(lhs, rhs) => assert_ne!(lhs, rhs),
+            _ => panic!("Expected an application"),
+        }
+    }
+
+    #[test]
+    fn test_undefine() {
+        let mut env = env_with(&[("ID", "\\x.x")]);
+        assert!(env.undefine("ID"));
+        assert!(!env.undefine("ID"));
+        assert!(env.is_empty());
+        let mut supply = NameSupply::new();
+        let expr = bound("ID", &mut supply);
+        assert_eq!(env.expand(expr.clone(), &mut supply), expr);
+    }
+
+    #[test]
+    fn test_types_follow_definitions() {
+        let mut env = env_with(&[]);
+        let id = bound("\\x.x", &mut NameSupply::new());
+        let ty = Type::arrow(Type::base("Nat"), Type::base("Nat"));
+        env.define_typed("I".to_string(), id.clone(), ty.clone());
+        env.define_typed("J".to_string(), id.clone(), ty.clone());
+        assert_eq!(env.system_f_types().get("I"), Some(&ty));
+        env.undefine("I");
+        // An untyped definition replaces the typed one entirely
+        env.define("J".to_string(), id);
+        assert!(env.system_f_types().is_empty());
+    }
+
+    #[test]
+    fn test_expansion_does_not_depend_on_name_order() {
+        // aa and zz were defined while bb and yy were still free, so the
+        // copies keep them free whichever name sorts first
+        for (outer, inner) in [("aa", "bb"), ("zz", "yy")] {
+            let env = env_with(&[(outer, inner), (inner, "\\x.x")]);
+            let mut supply = NameSupply::new();
+            let input = format!("{} {}", outer, inner);
+            let expr = env.expand(bound(&input, &mut supply), &mut supply);
+            assert_eq!(expr.format(false), format!("({} (\\x.x))", inner));
+        }
+    }
+}
@@ -1,88 +1,849 @@
-use crate::expr::{Expr, VarName};
+use crate::builtins::{Builtins, EvalError};
+use crate::church::{church_false, church_true};
+use crate::expr::{Delta, Expr, VarName};
+use std::collections::HashSet;
 
-/// Substitute a variable with a value in an expression
-fn replace(arg: &VarName, body: Expr, val: &Expr) -> Expr {
-    match body {
+/// Source of fresh binder IDs
+///
+/// IDs only need to be unique within one evaluation, so each evaluation owns
+/// its own supply and the IDs it hands out do not depend on anything that was
+/// evaluated before.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameSupply {
+    next: usize,
+}
+
+impl NameSupply {
+    /// Create a supply whose first ID is 1 (0 is reserved for free variables)
+    pub fn new() -> Self {
+        NameSupply { next: 1 }
+    }
+
+    /// Create a supply that never hands out an ID already used in `expr`
+    pub fn above(expr: &Expr) -> Self {
+        NameSupply {
+            next: max_id(expr) + 1,
+        }
+    }
+
+    /// Allocate an ID that no binder has used yet
+    pub fn fresh(&mut self) -> usize {
+        let id = self.next;
+        self.next += 1;
+        id
+    }
+}
+
+impl Default for NameSupply {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Largest variable ID used anywhere in `expr`
+fn max_id(expr: &Expr) -> usize {
+    match expr {
+        Expr::Var(name) => name.id,
+        Expr::Fun(name, _, body) => name.id.max(max_id(body)),
+        Expr::App(lhs, rhs) => max_id(lhs).max(max_id(rhs)),
+        Expr::Lit(_) | Expr::Prim(_) => 0,
+    }
+}
+
+/// Check whether `arg` occurs free in `expr`
+fn occurs_free(arg: &VarName, expr: &Expr) -> bool {
+    match expr {
+        Expr::Var(name) => name == arg,
+        Expr::Fun(name, _, body) => name != arg && occurs_free(arg, body),
+        Expr::App(lhs, rhs) => occurs_free(arg, lhs) || occurs_free(arg, rhs),
+        Expr::Lit(_) | Expr::Prim(_) => false,
+    }
+}
+
+/// Collect the names of all variables that occur free in `expr`
+pub(crate) fn free_names(expr: &Expr, bound: &mut Vec<VarName>, names: &mut HashSet<String>) {
+    match expr {
+        Expr::Var(name) => {
+            if !bound.contains(name) {
+                names.insert(name.name.clone());
+            }
+        }
+        Expr::Fun(name, _, body) => {
+            bound.push(name.clone());
+            free_names(body, bound, names);
+            bound.pop();
+        }
+        Expr::App(lhs, rhs) => {
+            free_names(lhs, bound, names);
+            free_names(rhs, bound, names);
+        }
+        Expr::Lit(_) | Expr::Prim(_) => {}
+    }
+}
+
+/// Collect every variable name used in `expr`, bound or free
+fn all_names(expr: &Expr, names: &mut HashSet<String>) {
+    match expr {
+        Expr::Var(name) => {
+            names.insert(name.name.clone());
+        }
+        Expr::Fun(name, _, body) => {
+            names.insert(name.name.clone());
+            all_names(body, names);
+        }
+        Expr::App(lhs, rhs) => {
+            all_names(lhs, names);
+            all_names(rhs, names);
+        }
+        Expr::Lit(_) | Expr::Prim(_) => {}
+    }
+}
+
+/// Rename the free occurrences of `from` in `expr` to `to`
+fn rename(expr: Expr, from: &VarName, to: &VarName) -> Expr {
+    match expr {
         Expr::Var(name) => {
-            if name.name == arg.name && name.id == arg.id {
-                val.clone()
+            if &name == from {
+                Expr::Var(to.clone())
             } else {
                 Expr::Var(name)
             }
         }
-        Expr::Fun(name, body) => Expr::Fun(name, Box::new(replace(arg, *body, val))),
+        Expr::Fun(name, ty, body) => {
+            if &name == from {
+                Expr::Fun(name, ty, body)
+            } else {
+                Expr::Fun(name, ty, Box::new(rename(*body, from, to)))
+            }
+        }
         Expr::App(lhs, rhs) => Expr::App(
-            Box::new(replace(arg, *lhs, val)),
-            Box::new(replace(arg, *rhs, val)),
+            Box::new(rename(*lhs, from, to)),
+            Box::new(rename(*rhs, from, to)),
         ),
+        Expr::Lit(_) | Expr::Prim(_) => expr,
     }
 }
 
-/// Evaluate a lambda calculus expression one step
-pub fn eval(expr: Expr) -> Expr {
+/// Copy an expression, giving every binder in the copy a fresh ID
+fn freshen(expr: &Expr, supply: &mut NameSupply) -> Expr {
     match expr {
-        Expr::Var(name) => Expr::Var(name),
-        Expr::Fun(arg, body) => {
-            let new_body = eval(*body);
-            Expr::Fun(arg, Box::new(new_body))
+        Expr::Var(name) => Expr::Var(name.clone()),
+        Expr::Fun(arg, ty, body) => {
+            let new_arg = VarName::new_bound(arg.name.clone(), supply.fresh());
+            let new_body = rename(freshen(body, supply), arg, &new_arg);
+            Expr::Fun(new_arg, ty.clone(), Box::new(new_body))
         }
-        Expr::App(lhs, rhs) => {
-            let lhs_expr = *lhs;
-            let new_lhs = eval(lhs_expr.clone());
+        Expr::App(lhs, rhs) => Expr::App(
+            Box::new(freshen(lhs, supply)),
+            Box::new(freshen(rhs, supply)),
+        ),
+        Expr::Lit(_) | Expr::Prim(_) => expr.clone(),
+    }
+}
+
+/// Pick a variant of `base` (by appending primes) that is not in `avoid`
+fn fresh_name(base: &str, avoid: &HashSet<String>) -> String {
+    let mut name = format!("{}'", base);
+    while avoid.contains(&name) {
+        name.push('\'');
+    }
+    name
+}
+
+/// Substitute a variable with a value in an expression, avoiding capture
+///
+/// Each copy of `val` gets fresh binder IDs, and binders in `body` whose
+/// names clash with a free variable of `val` are renamed with primes so
+/// that the result also reads correctly when printed without IDs.
+pub(crate) fn replace(arg: &VarName, body: Expr, val: &Expr, supply: &mut NameSupply) -> Expr {
+    replace_all(body, &[(arg, val)], supply)
+}
 
-            if new_lhs != lhs_expr {
-                return Expr::App(Box::new(new_lhs), rhs);
+/// Substitute several variables at once, like [`replace`]
+///
+/// The copies that are put in are not searched again, so a value that
+/// mentions one of the variables keeps it free.
+pub(crate) fn replace_all(body: Expr, vals: &[(&VarName, &Expr)], supply: &mut NameSupply) -> Expr {
+    let mut val_free = HashSet::new();
+    for (_, val) in vals {
+        free_names(val, &mut Vec::new(), &mut val_free);
+    }
+    substitute(body, vals, &val_free, supply)
+}
+
+fn substitute(
+    body: Expr,
+    vals: &[(&VarName, &Expr)],
+    val_free: &HashSet<String>,
+    supply: &mut NameSupply,
+) -> Expr {
+    match body {
+        Expr::Var(name) => match vals.iter().find(|(arg, _)| **arg == name) {
+            Some((_, val)) => freshen(val, supply),
+            None => Expr::Var(name),
+        },
+        Expr::Fun(name, ty, body) => {
+            if vals.iter().any(|(arg, _)| **arg == name) {
+                // The binder shadows one of the variables
+                let rest: Vec<(&VarName, &Expr)> = vals
+                    .iter()
+                    .filter(|(arg, _)| **arg != name)
+                    .copied()
+                    .collect();
+                let body = substitute(*body, &rest, val_free, supply);
+                return Expr::Fun(name, ty, Box::new(body));
             }
+            if !vals.iter().any(|(arg, _)| occurs_free(arg, &body)) {
+                return Expr::Fun(name, ty, body);
+            }
+            if val_free.contains(&name.name) {
+                let mut avoid = val_free.clone();
+                all_names(&body, &mut avoid);
+                let new_name = VarName::new_bound(fresh_name(&name.name, &avoid), supply.fresh());
+                let renamed = rename(*body, &name, &new_name);
+                return Expr::Fun(
+                    new_name,
+                    ty,
+                    Box::new(substitute(renamed, vals, val_free, supply)),
+                );
+            }
+            Expr::Fun(
+                name,
+                ty,
+                Box::new(substitute(*body, vals, val_free, supply)),
+            )
+        }
+        Expr::App(lhs, rhs) => Expr::App(
+            Box::new(substitute(*lhs, vals, val_free, supply)),
+            Box::new(substitute(*rhs, vals, val_free, supply)),
+        ),
+        Expr::Lit(_) | Expr::Prim(_) => body,
+    }
+}
+
+/// Order in which redexes are chosen during reduction
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strategy {
+    /// Leftmost-outermost redex first, reducing under abstractions
+    #[default]
+    NormalOrder,
+    /// Leftmost-innermost redex first, reducing under abstractions
+    ApplicativeOrder,
+    /// Leftmost-outermost redex first, never under abstractions or inside arguments
+    CallByName,
+    /// Arguments are reduced to values before substitution, never under abstractions
+    CallByValue,
+    /// Only the head redex, reducing under abstractions in head position
+    Head,
+}
+
+impl Strategy {
+    pub const ALL: [Strategy; 5] = [
+        Strategy::NormalOrder,
+        Strategy::ApplicativeOrder,
+        Strategy::CallByName,
+        Strategy::CallByValue,
+        Strategy::Head,
+    ];
+
+    /// Short name used by the CLI and the REPL
+    pub fn name(self) -> &'static str {
+        match self {
+            Strategy::NormalOrder => "normal",
+            Strategy::ApplicativeOrder => "applicative",
+            Strategy::CallByName => "cbn",
+            Strategy::CallByValue => "cbv",
+            Strategy::Head => "head",
+        }
+    }
+
+    pub(crate) fn reduces_under_lambda(self) -> bool {
+        matches!(
+            self,
+            Strategy::NormalOrder | Strategy::ApplicativeOrder | Strategy::Head
+        )
+    }
+}
+
+impl std::fmt::Display for Strategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for Strategy {
+    type Err = anyhow::Error;
 
-            match new_lhs {
-                Expr::Fun(arg, body) => {
-                    let evaled_rhs = eval(*rhs);
-                    replace(&arg, *body, &evaled_rhs)
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s {
+            "normal" | "no" => Ok(Strategy::NormalOrder),
+            "applicative" | "ao" => Ok(Strategy::ApplicativeOrder),
+            "cbn" | "name" => Ok(Strategy::CallByName),
+            "cbv" | "value" => Ok(Strategy::CallByValue),
+            "head" => Ok(Strategy::Head),
+            _ => Err(anyhow::anyhow!(
+                "unknown strategy: {} (expected normal, applicative, cbn, cbv or head)",
+                s
+            )),
+        }
+    }
+}
+
+/// Return `f` if `arg` and `body` form the eta redex `\arg.f arg`
+fn eta_redex<'a>(arg: &VarName, body: &'a Expr) -> Option<&'a Expr> {
+    match body {
+        Expr::App(lhs, rhs)
+            if matches!(rhs.as_ref(), Expr::Var(name) if name == arg) && !occurs_free(arg, lhs) =>
+        {
+            Some(lhs)
+        }
+        _ => None,
+    }
+}
+
+/// Split an application `f a1 ... an` into `f` and `[a1, ..., an]`
+fn spine(expr: &Expr) -> (&Expr, Vec<&Expr>) {
+    let mut head = expr;
+    let mut args = Vec::new();
+    while let Expr::App(lhs, rhs) = head {
+        args.push(rhs.as_ref());
+        head = lhs;
+    }
+    args.reverse();
+    (head, args)
+}
+
+/// Rebuild `head args...` with the argument at `index` replaced by `new_arg`
+fn rebuild(head: &Expr, args: &[&Expr], index: usize, new_arg: Expr) -> Expr {
+    let mut new_arg = Some(new_arg);
+    args.iter().enumerate().fold(head.clone(), |acc, (i, arg)| {
+        let arg = if i == index {
+            new_arg.take().unwrap()
+        } else {
+            (*arg).clone()
+        };
+        Expr::App(Box::new(acc), Box::new(arg))
+    })
+}
+
+/// How to reduce: the strategy, whether to eta-reduce and which builtins to call
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Reducer<'a> {
+    pub strategy: Strategy,
+    pub eta: bool,
+    pub builtins: Option<&'a Builtins>,
+}
+
+impl<'a> Reducer<'a> {
+    pub fn new(strategy: Strategy, eta: bool) -> Self {
+        Reducer {
+            strategy,
+            eta,
+            builtins: None,
+        }
+    }
+
+    /// Call the builtins in `builtins` when they are fully applied
+    pub fn with_builtins(self, builtins: &'a Builtins) -> Self {
+        Reducer {
+            builtins: Some(builtins),
+            ..self
+        }
+    }
+
+    /// Perform one reduction step, or return `Ok(None)` if no redex is reachable
+    ///
+    /// With `eta` set, eta redexes `\x.f x` are contracted alongside beta redexes,
+    /// in the same order the strategy visits abstractions. Weak strategies never
+    /// eta-reduce, since turning a value into an application can change whether
+    /// a call-by-value or call-by-name program terminates.
+    pub fn step(&self, expr: &Expr, supply: &mut NameSupply) -> Result<Option<Expr>, EvalError> {
+        Ok(self
+            .contract(expr, supply)?
+            .map(|contracted| contracted.term))
+    }
+
+    /// Iterate over the steps that reduce `expr`, until no redex is reachable
+    ///
+    /// The iterator stops after yielding an error from a builtin.
+    pub fn steps(&self, expr: Expr) -> Steps<'a> {
+        Steps {
+            reducer: *self,
+            supply: NameSupply::above(&expr),
+            current: expr,
+            index: 0,
+            done: false,
+        }
+    }
+
+    /// Like [`Reducer::step`], also telling which redex was contracted
+    ///
+    /// The path to the redex is built innermost move first while returning
+    /// from the recursion; [`Steps`] reverses it.
+    fn contract(
+        &self,
+        expr: &Expr,
+        supply: &mut NameSupply,
+    ) -> Result<Option<Contraction>, EvalError> {
+        let strategy = self.strategy;
+        match expr {
+            Expr::Var(_) => self.step_builtin(expr, supply),
+            Expr::Lit(_) | Expr::Prim(_) => Ok(None),
+            Expr::Fun(arg, ty, body) => {
+                if !strategy.reduces_under_lambda() {
+                    return Ok(None);
+                }
+                let contract_eta = || {
+                    eta_redex(arg, body)
+                        .filter(|_| self.eta)
+                        .map(|f| Contraction::new(f.clone(), Rule::Eta))
+                };
+                let step_body = |supply: &mut NameSupply| {
+                    Ok(self.contract(body, supply)?.map(|inner| {
+                        inner.within(Direction::Body, |new_body| {
+                            Expr::Fun(arg.clone(), ty.clone(), Box::new(new_body))
+                        })
+                    }))
+                };
+                if strategy == Strategy::ApplicativeOrder {
+                    Ok(step_body(supply)?.or_else(contract_eta))
+                } else {
+                    match contract_eta() {
+                        Some(contracted) => Ok(Some(contracted)),
+                        None => step_body(supply),
+                    }
+                }
+            }
+            Expr::App(lhs, rhs) => {
+                if let Some(next) = self.step_delta(expr, supply)? {
+                    return Ok(Some(next));
+                }
+                if let Some(next) = self.step_builtin(expr, supply)? {
+                    return Ok(Some(next));
                 }
-                _ => {
-                    let new_rhs = eval(*rhs);
-                    Expr::App(Box::new(new_lhs), Box::new(new_rhs))
+                let beta = |arg: &VarName, body: &Expr, supply: &mut NameSupply| {
+                    Contraction::new(replace(arg, body.clone(), rhs, supply), Rule::Beta)
+                };
+                let step_lhs = |supply: &mut NameSupply| {
+                    Ok::<_, EvalError>(self.contract(lhs, supply)?.map(|inner| {
+                        inner.within(Direction::Function, |new_lhs| {
+                            Expr::App(Box::new(new_lhs), rhs.clone())
+                        })
+                    }))
+                };
+                let step_rhs = |supply: &mut NameSupply| {
+                    Ok::<_, EvalError>(self.contract(rhs, supply)?.map(|inner| {
+                        inner.within(Direction::Argument, |new_rhs| {
+                            Expr::App(lhs.clone(), Box::new(new_rhs))
+                        })
+                    }))
+                };
+                match strategy {
+                    Strategy::NormalOrder | Strategy::CallByName | Strategy::Head => {
+                        if let Expr::Fun(arg, _, body) = lhs.as_ref() {
+                            return Ok(Some(beta(arg, body, supply)));
+                        }
+                        if let Some(next) = step_lhs(supply)? {
+                            return Ok(Some(next));
+                        }
+                        if strategy != Strategy::NormalOrder {
+                            return Ok(None);
+                        }
+                        step_rhs(supply)
+                    }
+                    Strategy::ApplicativeOrder | Strategy::CallByValue => {
+                        if let Some(next) = step_lhs(supply)? {
+                            return Ok(Some(next));
+                        }
+                        if let Some(next) = step_rhs(supply)? {
+                            return Ok(Some(next));
+                        }
+                        match lhs.as_ref() {
+                            Expr::Fun(arg, _, body) => Ok(Some(beta(arg, body, supply))),
+                            _ => Ok(None),
+                        }
+                    }
                 }
             }
         }
     }
+
+    /// Step argument `index` of `head args...`, if it has a redex
+    fn step_arg(
+        &self,
+        head: &Expr,
+        args: &[&Expr],
+        index: usize,
+        supply: &mut NameSupply,
+    ) -> Result<Option<Contraction>, EvalError> {
+        Ok(self.contract(args[index], supply)?.map(|mut inner| {
+            inner.redex.push(Direction::Argument);
+            for _ in index + 1..args.len() {
+                inner.redex.push(Direction::Function);
+            }
+            inner.term = rebuild(head, args, index, inner.term);
+            inner
+        }))
+    }
+
+    /// Step a saturated primitive application, if `expr` is one
+    ///
+    /// Strict arguments are reduced to literals first, whatever the strategy,
+    /// since the operation cannot fire without them. Returns `None` when `expr`
+    /// is not a primitive application or it is stuck, e.g. on a free variable.
+    fn step_delta(
+        &self,
+        expr: &Expr,
+        supply: &mut NameSupply,
+    ) -> Result<Option<Contraction>, EvalError> {
+        let (head, args) = spine(expr);
+        let Expr::Prim(op) = head else {
+            return Ok(None);
+        };
+        if args.len() != op.arity() {
+            return Ok(None);
+        }
+        let mut lits = Vec::new();
+        for (i, arg) in args.iter().enumerate().take(op.strict_args()) {
+            if let Expr::Lit(n) = arg {
+                lits.push(*n);
+                continue;
+            }
+            return self.step_arg(head, &args, i, supply);
+        }
+        Ok(op.delta(&lits).map(|delta| {
+            let term = match delta {
+                Delta::Lit(n) => Expr::Lit(n),
+                Delta::Bool(b) => {
+                    let value = if b { church_true() } else { church_false() };
+                    bind_vars(*value, supply)
+                }
+                Delta::Arg(i) => freshen(args[i], supply),
+            };
+            Contraction::new(term, Rule::Delta)
+        }))
+    }
+
+    /// Step a fully applied builtin, if `expr` is one
+    ///
+    /// Every argument is reduced to normal form in normal order, whatever the
+    /// strategy, so the closure never sees a redex, even under a lambda. Then
+    /// the builtin is called and its result takes the place of the
+    /// application.
+    fn step_builtin(
+        &self,
+        expr: &Expr,
+        supply: &mut NameSupply,
+    ) -> Result<Option<Contraction>, EvalError> {
+        let Some(builtins) = self.builtins else {
+            return Ok(None);
+        };
+        let (head, args) = spine(expr);
+        let Expr::Var(name) = head else {
+            return Ok(None);
+        };
+        if name.id != 0 || builtins.arity(&name.name) != Some(args.len()) {
+            return Ok(None);
+        }
+        let normal = Reducer {
+            strategy: Strategy::NormalOrder,
+            ..*self
+        };
+        for i in 0..args.len() {
+            if let Some(next) = normal.step_arg(head, &args, i, supply)? {
+                return Ok(Some(next));
+            }
+        }
+        let args: Vec<Expr> = args.into_iter().cloned().collect();
+        let result = builtins.call(&name.name, &args)?;
+        Ok(Some(Contraction::new(
+            freshen(&result, supply),
+            Rule::Builtin,
+        )))
+    }
+
+    /// Reduce for at most `max_iterations` steps, printing each one when
+    /// `print_step` is set
+    ///
+    /// A thin consumer of [`Reducer::steps`]. Returns the last term reached
+    /// and whether `max_iterations` steps were taken.
+    pub fn trace(
+        &self,
+        expr: Expr,
+        max_iterations: usize,
+        show_unique_id: bool,
+        print_step: bool,
+    ) -> Result<(Expr, bool), EvalError> {
+        let mut steps = self.steps(expr);
+
+        if print_step {
+            println!("Step 0:");
+            println!("{}", steps.term().format(show_unique_id));
+        }
+
+        let mut taken = 0;
+        while taken < max_iterations {
+            let Some(step) = steps.next() else {
+                break;
+            };
+            let step = step?;
+            if print_step {
+                println!("\nStep {}:", step.index);
+                println!("{}", steps.term().format(show_unique_id));
+            }
+            taken = step.index;
+        }
+
+        Ok((steps.into_term(), taken == max_iterations))
+    }
+
+    /// Reduce for at most `max_steps` steps
+    ///
+    /// Returns the result and whether the step limit was reached.
+    pub fn normalize(&self, expr: Expr, max_steps: usize) -> Result<(Expr, bool), EvalError> {
+        let mut steps = self.steps(expr);
+        for step in steps.by_ref().take(max_steps) {
+            step?;
+        }
+        let exceeded = steps.index == max_steps;
+        Ok((steps.into_term(), exceeded))
+    }
+}
+
+/// One move from a term into a subterm
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// Into the body of an abstraction
+    Body,
+    /// Into the function of an application
+    Function,
+    /// Into the argument of an application
+    Argument,
+}
+
+/// The kind of redex a step contracted
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rule {
+    /// `(\x.b) a => b[x := a]`
+    Beta,
+    /// `\x.f x => f`
+    Eta,
+    /// A saturated primitive applied to literals
+    Delta,
+    /// A fully applied builtin
+    Builtin,
+}
+
+impl std::fmt::Display for Rule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Rule::Beta => "beta",
+            Rule::Eta => "eta",
+            Rule::Delta => "delta",
+            Rule::Builtin => "builtin",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The result of contracting one redex, before it is numbered and measured
+struct Contraction {
+    term: Expr,
+    /// Path from the redex back up to the root
+    redex: Vec<Direction>,
+    rule: Rule,
+}
+
+impl Contraction {
+    fn new(term: Expr, rule: Rule) -> Self {
+        Contraction {
+            term,
+            redex: Vec::new(),
+            rule,
+        }
+    }
+
+    /// Place the contracted subterm back into its parent, reached by `direction`
+    fn within(mut self, direction: Direction, wrap: impl FnOnce(Expr) -> Expr) -> Self {
+        self.term = wrap(self.term);
+        self.redex.push(direction);
+        self
+    }
+}
+
+/// One reduction step, as yielded by [`Reducer::steps`]
+///
+/// A step does not carry the term it produced, so that iterating costs no
+/// more than reducing. The term is [`Steps::term`] until the next step is
+/// taken; see [`Steps`] for getting its size and depth.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    /// Step number, the first step being 1
+    pub index: usize,
+    /// Path from the root of the previous term to the contracted redex
+    pub redex: Vec<Direction>,
+    pub rule: Rule,
+}
+
+/// Iterator over the steps of a reduction, created by [`Reducer::steps`]
+///
+/// The term after each step is read with [`Steps::term`] between calls to
+/// `next`, and its metrics with [`Expr::size`] and [`Expr::depth`], which
+/// walk the term only when asked:
+///
+/// ```
+/// use lambdust::eval::{bind_vars, NameSupply, Reducer, Strategy};
+/// use lambdust::parser::parse;
+///
+/// let expr = bind_vars(*parse("(\\x.\\y.y x) ((\\z.z) w)").unwrap(), &mut NameSupply::new());
+/// let reducer = Reducer::new(Strategy::NormalOrder, false);
+/// let mut steps = reducer.steps(expr);
+/// let mut metrics = Vec::new();
+/// while let Some(step) = steps.next() {
+///     let step = step.unwrap();
+///     metrics.push((step.index, steps.term().size(), steps.term().depth()));
+/// }
+/// assert_eq!(metrics, [(1, 7, 5), (2, 4, 3)]);
+/// ```
+pub struct Steps<'a> {
+    reducer: Reducer<'a>,
+    supply: NameSupply,
+    current: Expr,
+    index: usize,
+    done: bool,
+}
+
+impl Steps<'_> {
+    /// The term reached so far
+    pub fn term(&self) -> &Expr {
+        &self.current
+    }
+
+    /// Stop iterating and take the term reached so far
+    pub fn into_term(self) -> Expr {
+        self.current
+    }
+}
+
+impl Iterator for Steps<'_> {
+    type Item = Result<Step, EvalError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.reducer.contract(&self.current, &mut self.supply) {
+            Ok(Some(contracted)) => {
+                self.index += 1;
+                self.current = contracted.term;
+                let mut redex = contracted.redex;
+                redex.reverse();
+                Some(Ok(Step {
+                    index: self.index,
+                    redex,
+                    rule: contracted.rule,
+                }))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(err) => {
+                self.done = true;
+                Some(Err(err))
+            }
+        }
+    }
+}
+
+/// Evaluate a lambda calculus expression one step using the given strategy
+///
+/// Returns the expression unchanged if it is already in normal form for that strategy.
+/// Use [`Reducer::step`] to also eta-reduce.
+pub fn eval(expr: Expr, strategy: Strategy, supply: &mut NameSupply) -> Expr {
+    Reducer::new(strategy, false)
+        .step(&expr, supply)
+        .expect("evaluation without builtins cannot fail")
+        .unwrap_or(expr)
 }
 
 /// Trace evaluation steps
+///
+/// Shorthand for [`Reducer::trace`] with beta reduction only.
 pub fn trace_eval(
     expr: Expr,
+    strategy: Strategy,
     max_iterations: usize,
     show_unique_id: bool,
     print_step: bool,
 ) -> (Expr, bool) {
-    let mut current = expr;
-
-    if print_step {
-        println!("Step 0:");
-        println!("{}", current.format(show_unique_id));
-    }
+    Reducer::new(strategy, false)
+        .trace(expr, max_iterations, show_unique_id, print_step)
+        .expect("evaluation without builtins cannot fail")
+}
 
-    for i in 1..=max_iterations {
-        let next = eval(current.clone());
+/// Result of comparing two terms with [`equivalent`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Equivalence {
+    /// Both sides reduce to alpha-equivalent terms
+    Equal,
+    /// Both sides have normal forms and they differ
+    NotEqual,
+    /// The step budget ran out before a verdict was reached
+    Unknown,
+}
 
-        if next == current {
-            // println!("\nReached normal form after {} step(s)", i - 1);
-            return (current, false);
+impl std::fmt::Display for Equivalence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Equivalence::Equal => write!(f, "true"),
+            Equivalence::NotEqual => write!(f, "false"),
+            Equivalence::Unknown => write!(f, "unknown"),
         }
+    }
+}
 
-        if print_step {
-            println!("\nStep {}:", i);
-            println!("{}", next.format(show_unique_id));
+/// Eta-reduce every `\x.f x` (with `x` not free in `f`) in an expression
+///
+/// Beta redexes are left alone, so this is usually applied to a beta normal
+/// form to obtain its beta-eta normal form.
+pub fn eta_normalize(expr: Expr) -> Expr {
+    match expr {
+        Expr::Var(name) => Expr::Var(name),
+        Expr::Fun(arg, ty, body) => {
+            let body = eta_normalize(*body);
+            match eta_redex(&arg, &body) {
+                Some(lhs) => lhs.clone(),
+                None => Expr::Fun(arg, ty, Box::new(body)),
+            }
         }
-
-        current = next;
+        Expr::App(lhs, rhs) => {
+            Expr::App(Box::new(eta_normalize(*lhs)), Box::new(eta_normalize(*rhs)))
+        }
+        Expr::Lit(_) | Expr::Prim(_) => expr,
     }
+}
 
-    // println!(
-    //     "\nStopped after {} iterations (may not be in normal form)",
-    //     max_iterations
-    // );
-    (current, true)
+/// Decide beta (or beta-eta) equivalence of two bound terms
+///
+/// Both sides are reduced in normal order for at most `budget` steps each.
+/// Since normal forms are unique, two different normal forms mean the terms
+/// are not equivalent; if either side runs out of steps the answer is
+/// `Unknown` unless the partial results already coincide.
+pub fn equivalent(a: &Expr, b: &Expr, budget: usize, eta: bool) -> Equivalence {
+    let (mut a, a_exceeded) = trace_eval(a.clone(), Strategy::NormalOrder, budget, false, false);
+    let (mut b, b_exceeded) = trace_eval(b.clone(), Strategy::NormalOrder, budget, false, false);
+    if !a_exceeded && !b_exceeded && eta {
+        a = eta_normalize(a);
+        b = eta_normalize(b);
+    }
+    if a.alpha_eq(&b) {
+        Equivalence::Equal
+    } else if a_exceeded || b_exceeded {
+        Equivalence::Unknown
+    } else {
+        Equivalence::NotEqual
+    }
 }
 
 /// Bind a variable in an expression body
@@ -94,31 +855,33 @@ fn bind_var(body: Expr, name: &VarName) -> Expr {
             }
             Expr::Var(body_name)
         }
-        Expr::Fun(body_name, fun_body) => Expr::Fun(body_name, Box::new(bind_var(*fun_body, name))),
+        Expr::Fun(body_name, ty, fun_body) => {
+            Expr::Fun(body_name, ty, Box::new(bind_var(*fun_body, name)))
+        }
         Expr::App(lhs, rhs) => Expr::App(
             Box::new(bind_var(*lhs, name)),
             Box::new(bind_var(*rhs, name)),
         ),
+        Expr::Lit(_) | Expr::Prim(_) => body,
     }
 }
 
 /// Assign unique IDs to all bound variables in an expression
-pub fn bind_vars(expr: Expr) -> Expr {
-    static mut VAR_ID: usize = 1;
+pub fn bind_vars(expr: Expr, supply: &mut NameSupply) -> Expr {
     match expr {
         Expr::Var(name) => Expr::Var(name),
-        Expr::Fun(mut arg, body) => {
-            arg.id = unsafe { VAR_ID };
-            unsafe { VAR_ID += 1 };
+        Expr::Fun(mut arg, ty, body) => {
+            arg.id = supply.fresh();
             let bound_body = bind_var(*body, &arg);
-            let transformed_body = bind_vars(bound_body);
-            Expr::Fun(arg, Box::new(transformed_body))
+            let transformed_body = bind_vars(bound_body, supply);
+            Expr::Fun(arg, ty, Box::new(transformed_body))
         }
         Expr::App(lhs, rhs) => {
-            let transformed_lhs = bind_vars(*lhs);
-            let transformed_rhs = bind_vars(*rhs);
+            let transformed_lhs = bind_vars(*lhs, supply);
+            let transformed_rhs = bind_vars(*rhs, supply);
             Expr::App(Box::new(transformed_lhs), Box::new(transformed_rhs))
         }
+        Expr::Lit(_) | Expr::Prim(_) => expr,
     }
 }
 
@@ -126,11 +889,16 @@ pub fn bind_vars(expr: Expr) -> Expr {
 mod tests {
     use super::*;
     use crate::expr::{app, fun, var};
+    use crate::parser::parse;
+
+    fn bound(input: &str) -> Expr {
+        bind_vars(*parse(input).unwrap(), &mut NameSupply::new())
+    }
 
     #[test]
     fn test_eval_variable() {
         let expr = *var("x".to_string());
-        let result = eval(expr.clone());
+        let result = eval(expr.clone(), Strategy::NormalOrder, &mut NameSupply::new());
         assert_eq!(result, expr);
     }
 
@@ -141,8 +909,9 @@ mod tests {
             fun("x".to_string(), var("x".to_string())),
             var("y".to_string()),
         );
-        let bound = bind_vars(expr);
-        let result = eval(bound);
+        let mut supply = NameSupply::new();
+        let bound = bind_vars(expr, &mut supply);
+        let result = eval(bound, Strategy::NormalOrder, &mut supply);
 
         // Result should be y (with id 0 since it's free)
         match result {
@@ -169,7 +938,362 @@ mod tests {
             id: 0,
         });
 
-        let result = replace(&arg, body, &val);
+        let result = replace(&arg, body, &val, &mut NameSupply::new());
         assert_eq!(result, val);
     }
+
+    #[test]
+    fn test_first_step_normal_vs_applicative() {
+        // (λx.λy.y) ((λz.z) w): normal order discards the argument,
+        // applicative order reduces it first
+        let expr = bound("(\\x.\\y.y) ((\\z.z) w)");
+        let mut supply = NameSupply::above(&expr);
+        let normal = eval(expr.clone(), Strategy::NormalOrder, &mut supply);
+        let applicative = eval(expr, Strategy::ApplicativeOrder, &mut supply);
+        assert_eq!(normal.format(false), "(\\y.y)");
+        assert_eq!(applicative.format(false), "((\\x.(\\y.y)) w)");
+    }
+
+    #[test]
+    fn test_divergent_argument() {
+        // (λx.y) Ω terminates under call-by-name but not under call-by-value
+        let expr = bound("(\\x.y) ((\\x.x x) (\\x.x x))");
+        let (result, exceeded) = trace_eval(expr.clone(), Strategy::CallByName, 10, false, false);
+        assert!(!exceeded);
+        assert_eq!(result.format(false), "y");
+
+        let (_, exceeded) = trace_eval(expr, Strategy::CallByValue, 10, false, false);
+        assert!(exceeded);
+    }
+
+    #[test]
+    fn test_weak_strategies_stop_at_lambda() {
+        let expr = bound("\\x.(\\y.y) x");
+        for strategy in [Strategy::CallByName, Strategy::CallByValue] {
+            let (result, exceeded) = trace_eval(expr.clone(), strategy, 10, false, false);
+            assert!(!exceeded);
+            assert_eq!(result, expr, "{} should not reduce under λ", strategy);
+        }
+        let (result, _) = trace_eval(expr, Strategy::NormalOrder, 10, false, false);
+        assert_eq!(result.format(false), "(\\x.x)");
+    }
+
+    #[test]
+    fn test_head_reduction_skips_arguments() {
+        let expr = bound("\\f.f ((\\y.y) z)");
+        let (result, exceeded) = trace_eval(expr.clone(), Strategy::Head, 10, false, false);
+        assert!(!exceeded);
+        assert_eq!(result, expr);
+
+        let (result, _) = trace_eval(expr, Strategy::NormalOrder, 10, false, false);
+        assert_eq!(result.format(false), "(\\f.(f z))");
+    }
+
+    #[test]
+    fn test_strategy_from_str() {
+        for strategy in Strategy::ALL {
+            assert_eq!(strategy.name().parse::<Strategy>().unwrap(), strategy);
+        }
+        assert!("lazy".parse::<Strategy>().is_err());
+    }
+
+    fn normalize(input: &str) -> Expr {
+        trace_eval(bound(input), Strategy::NormalOrder, 100, false, false).0
+    }
+
+    #[test]
+    fn test_duplicated_lambda_keeps_scopes() {
+        // (λf.f f) (λx.λy.x y) copies the argument twice; a naive substitution
+        // would reuse the binder IDs and produce λy.λy.y y
+        let result = normalize("(\\f.f f) (\\x.\\y.x y)");
+        assert_eq!(result.format(false), "(\\y.(\\y'.(y y')))");
+        match result {
+            Expr::Fun(outer, _, body) => match *body {
+                Expr::Fun(inner, _, body) => {
+                    assert_ne!(outer, inner);
+                    assert_eq!(
+                        *body,
+                        Expr::App(Box::new(Expr::Var(outer)), Box::new(Expr::Var(inner)))
+                    );
+                }
+                _ => panic!("Expected an inner abstraction"),
+            },
+            _ => panic!("Expected an abstraction"),
+        }
+    }
+
+    #[test]
+    fn test_free_variable_not_captured() {
+        // (λx.λy.x) y must not become the identity λy.y
+        let result = normalize("(\\x.\\y.x) y");
+        assert_eq!(result.format(false), "(\\y'.y)");
+        match result {
+            Expr::Fun(_, _, body) => assert_eq!(*body, *var("y".to_string())),
+            _ => panic!("Expected an abstraction"),
+        }
+    }
+
+    #[test]
+    fn test_rename_avoids_existing_primes() {
+        let result = normalize("(\\x.\\y.\\y'.x y y') (y y')");
+        assert_eq!(result.format(false), "(\\y''.(\\y'''.(((y y') y'') y''')))");
+    }
+
+    #[test]
+    fn test_skk_is_identity() {
+        let result = normalize("(\\x.\\y.\\z.x z (y z)) (\\x.\\y.x) (\\x.\\y.x)");
+        assert_eq!(result.format(false), "(\\z.z)");
+    }
+
+    #[test]
+    fn test_self_applied_numeral() {
+        // 2 2 = 4: the numeral is duplicated into its own body
+        let result = normalize("(\\f.\\x.f (f x)) (\\f.\\x.f (f x))");
+        assert_eq!(result.format(false), "(\\x.(\\x'.(x (x (x (x x'))))))");
+    }
+
+    #[test]
+    fn test_freshen_copies_get_distinct_ids() {
+        let term = bound("\\x.x");
+        let mut supply = NameSupply::above(&term);
+        let (a, b) = (freshen(&term, &mut supply), freshen(&term, &mut supply));
+        assert_eq!(a.format(false), b.format(false));
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn test_ids_are_deterministic_per_evaluation() {
+        let first = bound("\\x.\\y.x y");
+        let _ = bound("\\a.\\b.\\c.a");
+        let second = bound("\\x.\\y.x y");
+        assert_eq!(first, second);
+        assert_eq!(first.format(true), "(\\x1.(\\y2.(x1 y2)))");
+    }
+
+    #[test]
+    fn test_concurrent_evaluation() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<NameSupply>();
+        assert_send_sync::<Expr>();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| std::thread::spawn(|| normalize("(\\f.f f) (\\x.\\y.x y)")))
+            .collect();
+        let results: Vec<Expr> = handles.into_iter().map(|h| h.join().unwrap()).collect();
+        assert!(results.iter().all(|r| r == &results[0]));
+    }
+
+    #[test]
+    fn test_alpha_eq() {
+        assert!(bound("\\x.\\y.x y").alpha_eq(&bound("\\a.\\b.a b")));
+        assert!(!bound("\\x.\\y.x y").alpha_eq(&bound("\\x.\\y.y x")));
+        assert!(!bound("\\x.y").alpha_eq(&bound("\\x.z")));
+        // The derived PartialEq compares names and IDs literally
+        assert_ne!(bound("\\x.x"), bound("\\y.y"));
+    }
+
+    #[test]
+    fn test_equivalent() {
+        let add_1_2 = bound("(\\m.\\n.\\f.\\x.m f (n f x)) (\\f.\\x.f x) (\\f.\\x.f (f x))");
+        let three = bound("\\g.\\y.g (g (g y))");
+        let two = bound("\\f.\\x.f (f x)");
+        assert_eq!(equivalent(&add_1_2, &three, 100, false), Equivalence::Equal);
+        assert_eq!(
+            equivalent(&add_1_2, &two, 100, false),
+            Equivalence::NotEqual
+        );
+    }
+
+    #[test]
+    fn test_equivalent_unknown_without_normal_form() {
+        let omega = bound("(\\x.x x) (\\x.x x)");
+        let other = bound("(\\x.x x x) (\\x.x x x)");
+        assert_eq!(equivalent(&omega, &other, 50, false), Equivalence::Unknown);
+        assert_eq!(equivalent(&omega, &omega, 50, false), Equivalence::Equal);
+    }
+
+    #[test]
+    fn test_equivalent_up_to_eta() {
+        let expanded = bound("\\x.f x");
+        let f = bound("f");
+        assert_eq!(equivalent(&expanded, &f, 10, false), Equivalence::NotEqual);
+        assert_eq!(equivalent(&expanded, &f, 10, true), Equivalence::Equal);
+        // λx.x x is not an eta redex since x occurs in the function part
+        let self_app = bound("\\x.x x");
+        assert_eq!(
+            equivalent(&self_app, &bound("x"), 10, true),
+            Equivalence::NotEqual
+        );
+    }
+
+    #[test]
+    fn test_eta_reduction_is_optional() {
+        let expr = bound("\\x.f x");
+        let (result, _) = trace_eval(expr.clone(), Strategy::NormalOrder, 10, false, false);
+        assert_eq!(result, expr);
+        let (result, _) = Reducer::new(Strategy::NormalOrder, true)
+            .trace(expr, 10, false, false)
+            .unwrap();
+        assert_eq!(result.format(false), "f");
+    }
+
+    #[test]
+    fn test_eta_with_strong_strategies() {
+        // SUCC 0 = λf.λx.f x, whose beta-eta normal form is λf.f
+        let expr = bound("(\\n.\\f.\\x.f (n f x)) (\\f.\\x.x)");
+        for strategy in [Strategy::NormalOrder, Strategy::ApplicativeOrder] {
+            let (result, exceeded) = Reducer::new(strategy, true)
+                .trace(expr.clone(), 20, false, false)
+                .unwrap();
+            assert!(!exceeded);
+            assert_eq!(result.format(false), "(\\f.f)", "strategy {}", strategy);
+        }
+    }
+
+    #[test]
+    fn test_eta_skips_non_redexes() {
+        for input in ["\\x.x x", "\\x.f x x", "\\x.(f x) x"] {
+            let expr = bound(input);
+            let (result, _) = Reducer::new(Strategy::NormalOrder, true)
+                .trace(expr.clone(), 10, false, false)
+                .unwrap();
+            assert_eq!(result, expr, "{} is not an eta redex", input);
+        }
+    }
+
+    #[test]
+    fn test_weak_strategies_never_eta_reduce() {
+        let expr = bound("\\x.f x");
+        for strategy in [Strategy::CallByName, Strategy::CallByValue] {
+            let (result, _) = Reducer::new(strategy, true)
+                .trace(expr.clone(), 10, false, false)
+                .unwrap();
+            assert_eq!(result, expr);
+        }
+    }
+
+    #[test]
+    fn test_eta_normalize() {
+        assert_eq!(eta_normalize(bound("\\x.\\y.g x y")).format(false), "g");
+        assert_eq!(eta_normalize(bound("\\f.\\x.f x")).format(false), "(\\f.f)");
+        assert_eq!(
+            eta_normalize(bound("\\f.\\x.f (f x)")).format(false),
+            "(\\f.(\\x.(f (f x))))"
+        );
+    }
+
+    fn with_prims(input: &str) -> Expr {
+        let options = crate::parser::ParseOptions {
+            primitives: true,
+            ..Default::default()
+        };
+        let expr = crate::parser::parse_with(input, options).unwrap();
+        bind_vars(*expr, &mut NameSupply::new())
+    }
+
+    fn run(expr: Expr, strategy: Strategy) -> Expr {
+        trace_eval(expr, strategy, 100, false, false).0
+    }
+
+    #[test]
+    fn test_delta_arithmetic() {
+        for strategy in Strategy::ALL {
+            let result = run(with_prims("mul (add 2 3) (sub 10 4)"), strategy);
+            assert_eq!(result, Expr::Lit(30), "strategy {}", strategy);
+        }
+        let result = run(with_prims("(\\x.add x x) 21"), Strategy::CallByName);
+        assert_eq!(result, Expr::Lit(42));
+    }
+
+    #[test]
+    fn test_delta_eq_gives_church_boolean() {
+        let result = run(with_prims("eq 3 3 a b"), Strategy::NormalOrder);
+        assert_eq!(result.format(false), "a");
+        let result = run(with_prims("eq 3 4"), Strategy::NormalOrder);
+        assert!(result.alpha_eq(&bound("\\x.\\y.y")));
+    }
+
+    #[test]
+    fn test_delta_ifz_is_lazy_in_branches() {
+        // The untaken branch diverges, so only the condition may be forced
+        let result = run(
+            with_prims("ifz (sub 2 2) done ((\\x.x x) (\\x.x x))"),
+            Strategy::NormalOrder,
+        );
+        assert_eq!(result.format(false), "done");
+        let result = run(
+            with_prims("ifz 1 ((\\x.x x) (\\x.x x)) 7"),
+            Strategy::CallByName,
+        );
+        assert_eq!(result, Expr::Lit(7));
+    }
+
+    #[test]
+    fn test_delta_stuck_terms() {
+        // A free variable, a partial application and an overflow do not reduce
+        for input in ["add x 1", "add 1", "mul 9223372036854775807 2"] {
+            let expr = with_prims(input);
+            assert_eq!(run(expr.clone(), Strategy::NormalOrder), expr, "{}", input);
+        }
+    }
+
+    #[test]
+    fn test_steps_report_redex_and_metrics() {
+        let reducer = Reducer::new(Strategy::NormalOrder, false);
+        let mut steps = reducer.steps(bound("(\\x.\\y.y x) ((\\z.z) w)"));
+        let mut seen = Vec::new();
+        while let Some(step) = steps.next() {
+            let term = steps.term();
+            seen.push((step.unwrap(), term.format(false), term.size(), term.depth()));
+        }
+        assert_eq!(seen.len(), 2);
+        assert_eq!(seen[0].0.index, 1);
+        assert_eq!(seen[0].0.redex, []);
+        assert_eq!(seen[0].1, "(\\y.(y ((\\z.z) w)))");
+        assert_eq!(seen[1].0.index, 2);
+        assert_eq!(seen[1].0.redex, [Direction::Body, Direction::Argument]);
+        assert_eq!(seen[1].1, "(\\y.(y w))");
+        assert_eq!((seen[1].2, seen[1].3), (4, 3));
+        assert!(seen.iter().all(|(step, ..)| step.rule == Rule::Beta));
+    }
+
+    #[test]
+    fn test_steps_report_rules() {
+        let reducer = Reducer::new(Strategy::NormalOrder, true);
+        let step = reducer.steps(bound("\\x.f x")).next().unwrap().unwrap();
+        assert_eq!((step.rule, step.redex), (Rule::Eta, vec![]));
+
+        let options = crate::parser::ParseOptions {
+            primitives: true,
+            ..Default::default()
+        };
+        let expr = bind_vars(
+            *crate::parser::parse_with("ifz ((\\x.x) 0) a b", options).unwrap(),
+            &mut NameSupply::new(),
+        );
+        let reducer = Reducer::new(Strategy::CallByValue, false);
+        let mut iter = reducer.steps(expr);
+        let steps: Vec<Step> = iter.by_ref().map(Result::unwrap).collect();
+        assert_eq!(
+            steps[0].redex,
+            [
+                Direction::Function,
+                Direction::Function,
+                Direction::Argument
+            ]
+        );
+        assert_eq!(steps[0].rule, Rule::Beta);
+        assert_eq!(steps[1].rule, Rule::Delta);
+        assert_eq!(iter.term().format(false), "a");
+    }
+
+    #[test]
+    fn test_steps_can_be_limited() {
+        let reducer = Reducer::new(Strategy::NormalOrder, false);
+        let mut steps = reducer.steps(bound("(\\x.x x) (\\x.x x)"));
+        assert_eq!(steps.by_ref().take(5).count(), 5);
+        assert!(steps.term().alpha_eq(&bound("(\\y.y y) (\\y.y y)")));
+        let (_, exceeded) = reducer.normalize(bound("(\\x.x x) (\\x.x x)"), 5).unwrap();
+        assert!(exceeded);
+    }
 }
@@ -1,3 +1,6 @@
+use crate::debruijn::DbExpr;
+use crate::types::Type;
+
 #[derive(Clone, PartialEq, Debug)]
 pub struct VarName {
     pub(crate) name: String,
@@ -9,17 +12,105 @@ impl VarName {
         VarName { name, id: 0 }
     }
 
-    #[allow(dead_code)]
     pub(crate) fn new_bound(name: String, id: usize) -> Self {
         VarName { name, id }
     }
 }
 
+/// A built-in operation on integer literals
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrimOp {
+    /// `add m n`
+    Add,
+    /// `sub m n`
+    Sub,
+    /// `mul m n`
+    Mul,
+    /// `eq m n`, giving a Church boolean
+    Eq,
+    /// `ifz n a b`, giving `a` when `n` is 0 and `b` otherwise
+    IfZero,
+}
+
+/// What a saturated primitive application reduces to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Delta {
+    Lit(i64),
+    Bool(bool),
+    /// One of the arguments, by position
+    Arg(usize),
+}
+
+impl PrimOp {
+    pub const ALL: [PrimOp; 5] = [
+        PrimOp::Add,
+        PrimOp::Sub,
+        PrimOp::Mul,
+        PrimOp::Eq,
+        PrimOp::IfZero,
+    ];
+
+    /// Name used in source code
+    pub fn name(self) -> &'static str {
+        match self {
+            PrimOp::Add => "add",
+            PrimOp::Sub => "sub",
+            PrimOp::Mul => "mul",
+            PrimOp::Eq => "eq",
+            PrimOp::IfZero => "ifz",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<PrimOp> {
+        PrimOp::ALL.into_iter().find(|op| op.name() == name)
+    }
+
+    /// Number of arguments the operation takes
+    pub fn arity(self) -> usize {
+        match self {
+            PrimOp::IfZero => 3,
+            _ => 2,
+        }
+    }
+
+    /// Number of leading arguments that must be literals before it reduces
+    pub fn strict_args(self) -> usize {
+        match self {
+            PrimOp::IfZero => 1,
+            _ => 2,
+        }
+    }
+
+    /// Apply the delta rule to the strict arguments
+    ///
+    /// Returns `None` when the result overflows, leaving the application stuck.
+    pub fn delta(self, lits: &[i64]) -> Option<Delta> {
+        match self {
+            PrimOp::Add => lits[0].checked_add(lits[1]).map(Delta::Lit),
+            PrimOp::Sub => lits[0].checked_sub(lits[1]).map(Delta::Lit),
+            PrimOp::Mul => lits[0].checked_mul(lits[1]).map(Delta::Lit),
+            PrimOp::Eq => Some(Delta::Bool(lits[0] == lits[1])),
+            PrimOp::IfZero => Some(Delta::Arg(if lits[0] == 0 { 1 } else { 2 })),
+        }
+    }
+}
+
+impl std::fmt::Display for PrimOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 #[derive(Clone, PartialEq, Debug)]
 pub enum Expr {
     Var(VarName),
-    Fun(VarName, Box<Expr>),
+    /// Abstraction, with an optional annotation on the binder's type
+    Fun(VarName, Option<Type>, Box<Expr>),
     App(Box<Expr>, Box<Expr>),
+    /// Integer literal, only produced when primitives are enabled
+    Lit(i64),
+    /// Built-in operation, only produced when primitives are enabled
+    Prim(PrimOp),
 }
 
 pub fn var(name: String) -> Box<Expr> {
@@ -27,7 +118,7 @@ pub fn var(name: String) -> Box<Expr> {
 }
 
 pub fn fun(name: String, body: Box<Expr>) -> Box<Expr> {
-    Box::new(Expr::Fun(VarName::new_free(name), body))
+    Box::new(Expr::Fun(VarName::new_free(name), None, body))
 }
 
 pub fn app(f: Box<Expr>, x: Box<Expr>) -> Box<Expr> {
@@ -35,6 +126,29 @@ pub fn app(f: Box<Expr>, x: Box<Expr>) -> Box<Expr> {
 }
 
 impl Expr {
+    /// Check whether two expressions are equal up to renaming of bound variables
+    pub fn alpha_eq(&self, other: &Expr) -> bool {
+        DbExpr::from_expr(self) == DbExpr::from_expr(other)
+    }
+
+    /// Number of variables, abstractions, applications, literals and primitives
+    pub fn size(&self) -> usize {
+        match self {
+            Expr::Var(_) | Expr::Lit(_) | Expr::Prim(_) => 1,
+            Expr::Fun(_, _, body) => 1 + body.size(),
+            Expr::App(lhs, rhs) => 1 + lhs.size() + rhs.size(),
+        }
+    }
+
+    /// Length of the longest path from the root to a leaf, a leaf being 1 deep
+    pub fn depth(&self) -> usize {
+        match self {
+            Expr::Var(_) | Expr::Lit(_) | Expr::Prim(_) => 1,
+            Expr::Fun(_, _, body) => 1 + body.depth(),
+            Expr::App(lhs, rhs) => 1 + lhs.depth().max(rhs.depth()),
+        }
+    }
+
     /// Format the expression with optional unique IDs
     pub fn format(&self, show_id: bool) -> String {
         match self {
@@ -45,17 +159,22 @@ impl Expr {
                     name.name.clone()
                 }
             }
-            Expr::Fun(name, body) => {
-                let name_str = if show_id {
+            Expr::Fun(name, ty, body) => {
+                let mut name_str = if show_id {
                     format!("{}{}", name.name, name.id)
                 } else {
                     name.name.clone()
                 };
+                if let Some(ty) = ty {
+                    name_str = format!("{}:{}", name_str, ty);
+                }
                 format!("(\\{}.{})", name_str, body.format(show_id))
             }
             Expr::App(lhs, rhs) => {
                 format!("({} {})", lhs.format(show_id), rhs.format(show_id))
             }
+            Expr::Lit(n) => n.to_string(),
+            Expr::Prim(op) => op.name().to_string(),
         }
     }
 }
@@ -0,0 +1,357 @@
+//! Hindley–Milner type inference for unannotated terms
+//!
+//! This is Algorithm J: one substitution is threaded through the whole term
+//! and unification extends it in place. Binder annotations, where present,
+//! are respected, though quantified annotations are only compared as a whole
+//! rather than instantiated.
+//!
+//! The term syntax has no `let`, so lambda-bound names are always
+//! monomorphic. The only polymorphism comes from top-level [`Env`]
+//! definitions: [`infer_in`] infers and generalizes each definition a term
+//! uses, so every use of a definition gets its own instance of its type.
+
+use crate::env::Env;
+use crate::eval::free_names;
+use crate::expr::{Expr, PrimOp, VarName};
+use crate::types::{Type, TypeError, TypeErrorKind};
+use std::collections::{HashMap, HashSet};
+
+/// Why two types failed to unify
+enum UnifyError {
+    Mismatch,
+    Infinite(Type, Type),
+}
+
+struct Inference<'a> {
+    subst: HashMap<usize, Type>,
+    next: usize,
+    /// Types of the definitions in scope, with every variable quantified
+    globals: &'a HashMap<String, Type>,
+}
+
+impl<'a> Inference<'a> {
+    fn new(globals: &'a HashMap<String, Type>) -> Self {
+        Inference {
+            subst: HashMap::new(),
+            next: 0,
+            globals,
+        }
+    }
+
+    fn fresh(&mut self) -> Type {
+        self.next += 1;
+        Type::Var(self.next - 1)
+    }
+
+    /// Apply the substitution to `ty` until no bound variable is left
+    fn resolve(&self, ty: &Type) -> Type {
+        match ty {
+            Type::Var(n) => match self.subst.get(n) {
+                Some(bound) => self.resolve(bound),
+                None => ty.clone(),
+            },
+            Type::Arrow(param, result) => Type::arrow(self.resolve(param), self.resolve(result)),
+            Type::Base(_) | Type::Forall(_, _) => ty.clone(),
+        }
+    }
+
+    fn unify(&mut self, a: &Type, b: &Type) -> Result<(), UnifyError> {
+        match (self.resolve(a), self.resolve(b)) {
+            (Type::Var(m), Type::Var(n)) if m == n => Ok(()),
+            (Type::Var(n), ty) | (ty, Type::Var(n)) => {
+                if occurs(n, &ty) {
+                    return Err(UnifyError::Infinite(Type::Var(n), ty));
+                }
+                self.subst.insert(n, ty);
+                Ok(())
+            }
+            (Type::Arrow(p1, r1), Type::Arrow(p2, r2)) => {
+                self.unify(&p1, &p2)?;
+                self.unify(&r1, &r2)
+            }
+            (x, y) if x.alpha_eq(&y) => Ok(()),
+            _ => Err(UnifyError::Mismatch),
+        }
+    }
+
+    /// Replace every variable of a quantified type with a fresh one
+    fn instantiate(&mut self, ty: &Type) -> Type {
+        fn go(ty: &Type, fresh: &mut HashMap<usize, Type>, inf: &mut Inference) -> Type {
+            match ty {
+                Type::Var(n) => match fresh.get(n) {
+                    Some(var) => var.clone(),
+                    None => {
+                        let var = inf.fresh();
+                        fresh.insert(*n, var.clone());
+                        var
+                    }
+                },
+                Type::Arrow(param, result) => {
+                    Type::arrow(go(param, fresh, inf), go(result, fresh, inf))
+                }
+                Type::Base(_) | Type::Forall(_, _) => ty.clone(),
+            }
+        }
+        go(ty, &mut HashMap::new(), self)
+    }
+
+    /// Turn a unification failure into an error at `term`, with readable variables
+    fn error(&self, term: &Expr, err: UnifyError, expected: &Type, found: &Type) -> TypeError {
+        let kind = match err {
+            UnifyError::Mismatch => {
+                let [expected, found] = canonical([self.resolve(expected), self.resolve(found)]);
+                TypeErrorKind::Mismatch { expected, found }
+            }
+            UnifyError::Infinite(var, ty) => {
+                let [var, ty] = canonical([var, self.resolve(&ty)]);
+                TypeErrorKind::Infinite { var, ty }
+            }
+        };
+        TypeError::new(term, kind)
+    }
+
+    fn infer(&mut self, expr: &Expr, scope: &mut Vec<(VarName, Type)>) -> Result<Type, TypeError> {
+        match expr {
+            Expr::Var(name) => {
+                if let Some((_, ty)) = scope.iter().rev().find(|(bound, _)| bound == name) {
+                    return Ok(ty.clone());
+                }
+                match self.globals.get(&name.name) {
+                    Some(ty) => Ok(self.instantiate(ty)),
+                    None => Err(TypeError::new(
+                        expr,
+                        TypeErrorKind::Unbound(name.name.clone()),
+                    )),
+                }
+            }
+            Expr::Fun(arg, ty, body) => {
+                let param = match ty {
+                    Some(ty) => ty.clone(),
+                    None => self.fresh(),
+                };
+                scope.push((arg.clone(), param.clone()));
+                let result = self.infer(body, scope);
+                scope.pop();
+                Ok(Type::arrow(param, result?))
+            }
+            Expr::App(lhs, rhs) => {
+                let lhs_ty = self.infer(lhs, scope)?;
+                let rhs_ty = self.infer(rhs, scope)?;
+                match self.resolve(&lhs_ty) {
+                    Type::Arrow(param, result) => {
+                        self.unify(&param, &rhs_ty)
+                            .map_err(|err| self.error(rhs, err, &param, &rhs_ty))?;
+                        Ok(*result)
+                    }
+                    Type::Var(_) => {
+                        let result = self.fresh();
+                        let expected = Type::arrow(rhs_ty, result.clone());
+                        self.unify(&lhs_ty, &expected)
+                            .map_err(|err| self.error(expr, err, &expected, &lhs_ty))?;
+                        Ok(result)
+                    }
+                    found @ (Type::Base(_) | Type::Forall(_, _)) => {
+                        Err(TypeError::new(lhs, TypeErrorKind::NotAFunction(found)))
+                    }
+                }
+            }
+            Expr::Lit(_) => Ok(Type::int()),
+            Expr::Prim(op) => {
+                let int = Type::int;
+                Ok(match op {
+                    PrimOp::Add | PrimOp::Sub | PrimOp::Mul => {
+                        Type::arrow(int(), Type::arrow(int(), int()))
+                    }
+                    // The result is a Church boolean, a->a->a
+                    PrimOp::Eq => {
+                        let a = self.fresh();
+                        let boolean = Type::arrow(a.clone(), Type::arrow(a.clone(), a));
+                        Type::arrow(int(), Type::arrow(int(), boolean))
+                    }
+                    PrimOp::IfZero => {
+                        let a = self.fresh();
+                        Type::arrow(int(), Type::arrow(a.clone(), Type::arrow(a.clone(), a)))
+                    }
+                })
+            }
+        }
+    }
+}
+
+/// Check whether the variable `n` occurs in `ty`
+fn occurs(n: usize, ty: &Type) -> bool {
+    match ty {
+        Type::Var(m) => *m == n,
+        Type::Arrow(param, result) => occurs(n, param) || occurs(n, result),
+        Type::Base(_) | Type::Forall(_, _) => false,
+    }
+}
+
+/// Rename the variables of `types` to 0, 1, ... in order of appearance
+fn canonical<const N: usize>(types: [Type; N]) -> [Type; N] {
+    fn go(ty: &Type, names: &mut HashMap<usize, usize>) -> Type {
+        match ty {
+            Type::Var(n) => {
+                let next = names.len();
+                Type::Var(*names.entry(*n).or_insert(next))
+            }
+            Type::Arrow(param, result) => Type::arrow(go(param, names), go(result, names)),
+            Type::Base(_) | Type::Forall(_, _) => ty.clone(),
+        }
+    }
+    let mut names = HashMap::new();
+    types.map(|ty| go(&ty, &mut names))
+}
+
+/// Infer the principal type of a closed term
+///
+/// Free variables are reported as unbound. Type variables in the result are
+/// numbered in order of appearance, so `\x.\y.x` gets `a->b->a`.
+pub fn infer(expr: &Expr) -> Result<Type, TypeError> {
+    infer_with(expr, &HashMap::new())
+}
+
+/// Infer the principal type of a term whose free variables name `globals`
+///
+/// Every type variable in a global's type is quantified: each occurrence of
+/// the name gets a fresh copy, as if the global were bound by `let`.
+pub fn infer_with(expr: &Expr, globals: &HashMap<String, Type>) -> Result<Type, TypeError> {
+    let mut inference = Inference::new(globals);
+    let ty = inference.infer(expr, &mut Vec::new())?;
+    let [ty] = canonical([inference.resolve(&ty)]);
+    Ok(ty)
+}
+
+/// Infer the type of a bound term, treating the definitions it uses as `let` bindings
+///
+/// Each definition the term refers to is inferred on its own and generalized,
+/// so `ID` can be used at two different types in one term. An error inside a
+/// definition is reported as is.
+pub fn infer_in(expr: &Expr, env: &Env) -> Result<Type, TypeError> {
+    let mut names = HashSet::new();
+    free_names(expr, &mut Vec::new(), &mut names);
+    let mut globals = HashMap::new();
+    for name in names {
+        if let Some(def) = env.get(&name) {
+            globals.insert(name, infer(def)?);
+        }
+    }
+    infer_with(expr, &globals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::{bind_vars, NameSupply};
+    use crate::parser::{parse, parse_with, ParseOptions};
+
+    fn bound(input: &str) -> Expr {
+        bind_vars(*parse(input).unwrap(), &mut NameSupply::new())
+    }
+
+    fn type_str(input: &str) -> Result<String, String> {
+        infer(&bound(input))
+            .map(|ty| ty.to_string())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn test_principal_types() {
+        assert_eq!(type_str("\\x.x"), Ok("a->a".to_string()));
+        assert_eq!(type_str("\\x.\\y.x"), Ok("a->b->a".to_string()));
+        assert_eq!(type_str("\\f.\\x.f (f x)"), Ok("(a->a)->a->a".to_string()));
+        assert_eq!(
+            type_str("\\x.\\y.\\z.x z (y z)"),
+            Ok("(a->b->c)->(a->b)->a->c".to_string())
+        );
+        assert_eq!(
+            type_str("\\f.\\g.\\x.f (g x)"),
+            Ok("(a->b)->(c->a)->c->b".to_string())
+        );
+    }
+
+    #[test]
+    fn test_church_numerals_share_a_type() {
+        assert_eq!(type_str("3"), type_str("\\f.\\x.f (f x)"));
+        assert_eq!(
+            type_str("\\m.\\n.\\f.\\x.m f (n f x)"),
+            Ok("(a->b->c)->(a->d->b)->a->d->c".to_string())
+        );
+    }
+
+    #[test]
+    fn test_occurs_check() {
+        assert_eq!(
+            type_str("\\x.x x"),
+            Err("occurs check: a occurs in a->b at (x x)".to_string())
+        );
+        assert!(type_str("\\f.(\\x.f (x x)) (\\x.f (x x))").is_err());
+    }
+
+    #[test]
+    fn test_annotations_are_respected() {
+        assert_eq!(type_str("\\x:Nat.\\y.x"), Ok("Nat->a->Nat".to_string()));
+        assert_eq!(
+            type_str("\\f.f (\\x:Nat.x) (\\y:Bool.y)"),
+            Ok("((Nat->Nat)->(Bool->Bool)->a)->a".to_string())
+        );
+        assert_eq!(
+            type_str("\\g.(\\f:Nat->Nat.f) (\\b:Bool.b)"),
+            Err("expected Nat->Nat, found Bool->Bool at (\\b:Bool.b)".to_string())
+        );
+        assert_eq!(
+            type_str("\\x:Nat.x x"),
+            Err("cannot apply a term of type Nat at x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_lambda_bound_names_are_monomorphic() {
+        assert_eq!(
+            type_str("(\\id.\\f.f (id (\\x:Nat.x)) (id (\\y:Bool.y))) (\\x.x)"),
+            Err("expected Nat->Nat, found Bool->Bool at (\\y:Bool.y)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_definitions_are_polymorphic() {
+        let mut env = Env::new();
+        env.define("ID".to_string(), bound("\\x.x"));
+        let expr = bound("\\f.f (ID (\\x:Nat.x)) (ID (\\y:Bool.y))");
+        assert_eq!(
+            infer_in(&expr, &env).unwrap().to_string(),
+            "((Nat->Nat)->(Bool->Bool)->a)->a"
+        );
+        // A binder named ID shadows the definition
+        let expr = bound("\\ID.ID (\\x:Nat.x)");
+        assert_eq!(
+            infer_in(&expr, &env).unwrap().to_string(),
+            "((Nat->Nat)->a)->a"
+        );
+        assert_eq!(
+            infer_in(&bound("f ID"), &env).unwrap_err().to_string(),
+            "unbound variable f at f"
+        );
+    }
+
+    #[test]
+    fn test_primitives() {
+        let options = ParseOptions {
+            primitives: true,
+            ..Default::default()
+        };
+        let infer_str = |input: &str| {
+            infer(&bind_vars(
+                *parse_with(input, options).unwrap(),
+                &mut NameSupply::new(),
+            ))
+            .map(|ty| ty.to_string())
+        };
+        assert_eq!(
+            infer_str("\\n.ifz n 1 (mul n 2)"),
+            Ok("Int->Int".to_string())
+        );
+        assert_eq!(infer_str("eq 1 2"), Ok("a->a->a".to_string()));
+        assert!(infer_str("add 1 (\\x.x)").is_err());
+    }
+}
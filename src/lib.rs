@@ -4,12 +4,33 @@
 //! implementations of Church encodings for booleans and numerals.
 
 pub mod args;
+pub mod binary;
+pub mod blc;
+pub mod builtins;
 pub mod church;
+pub mod combinators;
+pub mod debruijn;
+pub mod decode;
+pub mod diagram;
+pub mod dot;
+pub mod env;
 pub mod eval;
 pub mod examples;
 pub mod expr;
+pub mod infer;
+pub mod parigot;
 pub mod parser;
+pub mod prelude;
+pub mod scott;
+pub mod signed;
+pub mod systemf;
+#[cfg(test)]
+mod test_support;
+pub mod types;
 
 // Re-export commonly used items
-pub use eval::{bind_vars, eval, trace_eval};
+pub use builtins::{Builtins, EvalError};
+pub use debruijn::DbExpr;
+pub use env::Env;
+pub use eval::{bind_vars, eval, trace_eval, NameSupply, Reducer, Step, Strategy};
 pub use expr::{app, fun, var, Expr, VarName};
@@ -1,14 +1,125 @@
 //! Lambda calculus interpreter CLI
 
 use clap::Parser;
-use lambdust::eval::{bind_vars, trace_eval};
-use lambdust::parser::parse;
-use std::io::Write;
+use lambdust::args::Args;
+use lambdust::blc::{self, IoMode};
+use lambdust::combinators::{compile, Abstraction};
+use lambdust::debruijn::DbExpr;
+use lambdust::decode::annotate;
+use lambdust::diagram::{self, animation, Diagram};
+use lambdust::dot::{self, reduction_graph};
+use lambdust::env::Env;
+use lambdust::eval::{bind_vars, equivalent, NameSupply, Reducer, Strategy};
+use lambdust::expr::Expr;
+use lambdust::infer::infer_in;
+use lambdust::parser::{
+    parse_program, parse_statement, parse_systemf_statement, parse_with, NumeralEncoding, Statement,
+};
+use lambdust::prelude::prelude;
+use lambdust::systemf::{erase, type_of_with};
+use lambdust::types::type_of;
+use std::io::{Read, Write};
+use std::path::Path;
 
-mod args;
+/// Evaluate an expression in the given environment and print the result
+fn evaluate(expr: Expr, env: &Env, args: &Args, system_f: bool) {
+    let mut supply = NameSupply::new();
+    let bound = bind_vars(expr, &mut supply);
+    let inferred = (args.types && !system_f).then(|| infer_in(&bound, env));
+    let result = Reducer::new(args.strategy, args.eta)
+        .trace(
+            env.expand(bound, &mut supply),
+            args.trace,
+            args.unique_id,
+            args.print_step,
+        )
+        .expect("evaluation without builtins cannot fail");
+    if args.debruijn {
+        println!("{}", DbExpr::from_expr(&result.0));
+    } else {
+        println!("{}", result.0.format(args.unique_id));
+    }
+    if result.1 {
+        println!("...");
+    } else if let Some(note) = annotate(&result.0) {
+        println!("=> {}", note);
+    }
+    match inferred {
+        Some(Ok(ty)) => println!(":: {}", ty),
+        Some(Err(err)) => println!(":: no type ({})", err),
+        None => {}
+    }
+}
+
+/// Run a binary lambda calculus program from `path` on stdin
+fn run_blc(path: &Path, mode: IoMode) -> anyhow::Result<()> {
+    let contents = std::fs::read(path)
+        .map_err(|err| anyhow::anyhow!("cannot read {}: {}", path.display(), err))?;
+    let program = blc::read_program(&contents)
+        .map_err(|err| anyhow::anyhow!("{}: {}", path.display(), err))?;
+    let mut input = Vec::new();
+    std::io::stdin().read_to_end(&mut input)?;
+    let output = blc::run(&program, &input, mode, blc::DEFAULT_STEPS)?;
+    std::io::stdout().write_all(&output)?;
+    Ok(())
+}
+
+/// Read a source file and add its definitions to `env`
+///
+/// When `run` is set, the expressions in the file are evaluated in order as
+/// well; otherwise they are skipped. Returns the number of definitions.
+fn load_file(path: &Path, env: &mut Env, args: &Args, run: bool) -> anyhow::Result<usize> {
+    let source = std::fs::read_to_string(path)
+        .map_err(|err| anyhow::anyhow!("cannot read {}: {}", path.display(), err))?;
+    let program = parse_program(&source, args.parse_options())
+        .map_err(|err| anyhow::anyhow!("{}: {}", path.display(), err))?;
+
+    let mut count = 0;
+    for statement in program {
+        match statement {
+            Statement::Define(name, e) => {
+                env.define(name, bind_vars(*e, &mut NameSupply::new()));
+                count += 1;
+            }
+            Statement::Eval(e) => {
+                if run {
+                    evaluate(*e, env, args, false);
+                }
+            }
+        }
+    }
+    Ok(count)
+}
 
 fn main() {
-    let mut args = args::Args::parse();
+    let mut args = Args::parse();
+    let mut env = if args.no_prelude {
+        Env::new()
+    } else {
+        prelude()
+    };
+
+    if let Some(mode) = args.blc {
+        let result = match &args.file {
+            Some(path) => run_blc(path, mode),
+            None => Err(anyhow::anyhow!("--blc expects a program file")),
+        };
+        if let Err(err) = result {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if let Some(path) = args.file.clone() {
+        if let Err(err) = load_file(&path, &mut env, &args, true) {
+            eprintln!("Error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    let mut system_f = false;
 
     println!("Lambdust");
     loop {
@@ -16,7 +127,10 @@ fn main() {
         std::io::stdout().flush().unwrap();
 
         let mut input = String::new();
-        std::io::stdin().read_line(&mut input).unwrap();
+        if std::io::stdin().read_line(&mut input).unwrap() == 0 {
+            println!();
+            break;
+        }
 
         let trimmed = input.trim();
 
@@ -47,6 +161,53 @@ fn main() {
                         "  :t, :trace [num]   - Show or set trace limit (current: {})",
                         args.trace
                     );
+                    println!("  :l, :load path     - Load definitions from a file");
+                    println!("  :env               - List definitions");
+                    println!("  :undef name        - Remove a definition");
+                    println!("  :eq a == b         - Check whether two terms are equivalent");
+                    println!("  :type e            - Show the simple type of an annotated term");
+                    println!("  :infer e           - Infer the principal type of a term");
+                    println!(
+                        "  :blc e             - Encode a closed term in binary lambda calculus"
+                    );
+                    println!(
+                        "  :dot path e        - Write the syntax tree of a term as a DOT file"
+                    );
+                    println!(
+                        "  :graph path e      - Write the reduction graph of a term as a DOT file"
+                    );
+                    println!("  :diagram e         - Draw the lambda diagram of a term");
+                    println!("  :svg path e        - Write the lambda diagram of a term as SVG");
+                    println!("  :animate path e    - Write the diagrams of each trace step as an animated SVG");
+                    println!("  :ski e             - Translate a term to S, K, I and Turner's combinators");
+                    println!(
+                        "  :types             - Toggle showing inferred types (current: {})",
+                        args.types
+                    );
+                    println!(
+                        "  :f, :systemf       - Toggle System F input (current: {})",
+                        system_f
+                    );
+                    println!(
+                        "  :d, :debruijn      - Toggle De Bruijn output (current: {})",
+                        args.debruijn
+                    );
+                    println!(
+                        "  :eta               - Toggle eta-reduction (current: {})",
+                        args.eta
+                    );
+                    println!(
+                        "  :n, :numerals [e]  - Show or set the literal encoding (current: {})",
+                        args.numerals
+                    );
+                    println!(
+                        "  :prim              - Toggle native integer primitives (current: {})",
+                        args.primitives
+                    );
+                    println!(
+                        "  :s, :strategy [s]  - Show or set reduction strategy (current: {})",
+                        args.strategy
+                    );
                 }
                 ":p" | ":print" => {
                     args.print_step = !args.print_step;
@@ -56,6 +217,233 @@ fn main() {
                     args.unique_id = !args.unique_id;
                     println!("Unique ID: {}", args.unique_id);
                 }
+                ":l" | ":load" => {
+                    let path = trimmed[command.len()..].trim();
+                    if path.is_empty() {
+                        println!("Error: :load expects a file path");
+                    } else {
+                        match load_file(Path::new(path), &mut env, &args, false) {
+                            Ok(count) => println!("Loaded {} definition(s) from {}", count, path),
+                            Err(err) => println!("Error: {}", err),
+                        }
+                    }
+                }
+                ":env" => {
+                    if env.is_empty() {
+                        println!("No definitions");
+                    }
+                    for (name, expr) in env.iter() {
+                        println!("{} = {}", name, expr.format(args.unique_id));
+                    }
+                }
+                ":undef" => {
+                    if parts.len() > 1 {
+                        for name in &parts[1..] {
+                            if env.undefine(name) {
+                                println!("Removed {}", name);
+                            } else {
+                                println!("Error: {} is not defined", name);
+                            }
+                        }
+                    } else {
+                        println!("Error: :undef expects a name");
+                    }
+                }
+                ":eq" => {
+                    let rest = trimmed[command.len()..].trim();
+                    let Some((lhs, rhs)) = rest.split_once("==") else {
+                        println!(
+                            "Error: :eq expects two terms separated by ==, e.g. :eq \\x.x == \\y.y"
+                        );
+                        continue;
+                    };
+                    let options = args.parse_options();
+                    match (parse_with(lhs, options), parse_with(rhs, options)) {
+                        (Ok(lhs), Ok(rhs)) => {
+                            let mut supply = NameSupply::new();
+                            let a = bind_vars(*lhs, &mut supply);
+                            let a = env.expand(a, &mut supply);
+                            let b = bind_vars(*rhs, &mut supply);
+                            let b = env.expand(b, &mut supply);
+                            println!("{}", equivalent(&a, &b, args.trace, args.eta));
+                        }
+                        (Err(err), _) | (_, Err(err)) => println!("Error: {}", err),
+                    }
+                }
+                ":type" => {
+                    let rest = trimmed[command.len()..].trim();
+                    match parse_with(rest, args.parse_options()) {
+                        Ok(e) => {
+                            let mut supply = NameSupply::new();
+                            let bound = bind_vars(*e, &mut supply);
+                            match type_of(&env.expand(bound, &mut supply)) {
+                                Ok(ty) => println!("{}", ty),
+                                Err(err) => println!("Error: {}", err),
+                            }
+                        }
+                        Err(err) => println!("Error: {}", err),
+                    }
+                }
+                ":infer" => {
+                    let rest = trimmed[command.len()..].trim();
+                    match parse_with(rest, args.parse_options()) {
+                        Ok(e) => match infer_in(&bind_vars(*e, &mut NameSupply::new()), &env) {
+                            Ok(ty) => println!("{}", ty),
+                            Err(err) => println!("Error: {}", err),
+                        },
+                        Err(err) => println!("Error: {}", err),
+                    }
+                }
+                ":f" | ":systemf" => {
+                    system_f = !system_f;
+                    println!("System F: {}", system_f);
+                }
+                ":blc" => {
+                    let rest = trimmed[command.len()..].trim();
+                    match parse_with(rest, args.parse_options()) {
+                        Ok(e) => {
+                            let mut supply = NameSupply::new();
+                            let bound = bind_vars(*e, &mut supply);
+                            match blc::encode(&env.expand(bound, &mut supply)) {
+                                Ok(bits) => println!("{} ({} bits)", bits, bits.len()),
+                                Err(err) => println!("Error: {}", err),
+                            }
+                        }
+                        Err(err) => println!("Error: {}", err),
+                    }
+                }
+                ":dot" | ":graph" => {
+                    let rest = trimmed[command.len()..].trim();
+                    let Some((path, term)) = rest.split_once(char::is_whitespace) else {
+                        println!("Error: {} expects a file path and a term", command);
+                        continue;
+                    };
+                    match parse_with(term, args.parse_options()) {
+                        Ok(e) => {
+                            let mut supply = NameSupply::new();
+                            let bound = bind_vars(*e, &mut supply);
+                            let expanded = env.expand(bound, &mut supply);
+                            let (contents, summary) = if command == ":dot" {
+                                (expanded.to_dot(), "syntax tree".to_string())
+                            } else {
+                                let graph = reduction_graph(&expanded, dot::DEFAULT_TERMS);
+                                let mut summary = format!(
+                                    "{} term(s) and {} step(s)",
+                                    graph.terms.len(),
+                                    graph.edges.len()
+                                );
+                                if !graph.complete {
+                                    summary.push_str(&format!(
+                                        ", stopped at {} terms",
+                                        dot::DEFAULT_TERMS
+                                    ));
+                                }
+                                (graph.to_dot(), summary)
+                            };
+                            match std::fs::write(path, contents) {
+                                Ok(()) => println!("Wrote {} to {}", summary, path),
+                                Err(err) => println!("Error: cannot write {}: {}", path, err),
+                            }
+                        }
+                        Err(err) => println!("Error: {}", err),
+                    }
+                }
+                ":diagram" => {
+                    let rest = trimmed[command.len()..].trim();
+                    match parse_with(rest, args.parse_options()) {
+                        Ok(e) => {
+                            let mut supply = NameSupply::new();
+                            let bound = bind_vars(*e, &mut supply);
+                            match Diagram::new(&env.expand(bound, &mut supply)) {
+                                Some(d) => print!("{}", d.to_blocks()),
+                                None => println!("Error: primitives have no diagram"),
+                            }
+                        }
+                        Err(err) => println!("Error: {}", err),
+                    }
+                }
+                ":svg" | ":animate" => {
+                    let rest = trimmed[command.len()..].trim();
+                    let Some((path, term)) = rest.split_once(char::is_whitespace) else {
+                        println!("Error: {} expects a file path and a term", command);
+                        continue;
+                    };
+                    match parse_with(term, args.parse_options()) {
+                        Ok(e) => {
+                            let mut supply = NameSupply::new();
+                            let bound = bind_vars(*e, &mut supply);
+                            let expanded = env.expand(bound, &mut supply);
+                            let rendered = if command == ":svg" {
+                                Diagram::new(&expanded).map(|d| {
+                                    (d.to_svg(diagram::DEFAULT_SCALE), "diagram".to_string())
+                                })
+                            } else {
+                                diagram::frames(
+                                    expanded,
+                                    &Reducer::new(args.strategy, args.eta),
+                                    args.trace,
+                                )
+                                .map(|frames| {
+                                    let svg = animation(
+                                        &frames,
+                                        diagram::DEFAULT_SCALE,
+                                        diagram::DEFAULT_FRAME_SECONDS,
+                                    );
+                                    (svg, format!("{} frame(s)", frames.len()))
+                                })
+                            };
+                            match rendered {
+                                Some((svg, summary)) => match std::fs::write(path, svg) {
+                                    Ok(()) => println!("Wrote {} to {}", summary, path),
+                                    Err(err) => println!("Error: cannot write {}: {}", path, err),
+                                },
+                                None => println!("Error: primitives have no diagram"),
+                            }
+                        }
+                        Err(err) => println!("Error: {}", err),
+                    }
+                }
+                ":ski" => {
+                    let rest = trimmed[command.len()..].trim();
+                    match parse_with(rest, args.parse_options()) {
+                        Ok(e) => {
+                            let mut supply = NameSupply::new();
+                            let bound = bind_vars(*e, &mut supply);
+                            let expanded = env.expand(bound, &mut supply);
+                            for (label, abstraction) in [
+                                ("naive", Abstraction::Naive),
+                                ("turner", Abstraction::Turner),
+                            ] {
+                                match compile(&expanded, abstraction) {
+                                    Some(comb) => {
+                                        println!("{:<6} (size {}): {}", label, comb.size(), comb)
+                                    }
+                                    None => {
+                                        println!("Error: primitives have no combinator form");
+                                        break;
+                                    }
+                                }
+                            }
+                        }
+                        Err(err) => println!("Error: {}", err),
+                    }
+                }
+                ":types" => {
+                    args.types = !args.types;
+                    println!("Show types: {}", args.types);
+                }
+                ":d" | ":debruijn" => {
+                    args.debruijn = !args.debruijn;
+                    println!("De Bruijn output: {}", args.debruijn);
+                }
+                ":eta" => {
+                    args.eta = !args.eta;
+                    println!("Eta-reduction: {}", args.eta);
+                }
+                ":prim" => {
+                    args.primitives = !args.primitives;
+                    println!("Primitives: {}", args.primitives);
+                }
                 ":t" | ":trace" => {
                     if parts.len() > 1 {
                         match parts[1].parse::<usize>() {
@@ -69,6 +457,32 @@ fn main() {
                         println!("Current trace limit: {}", args.trace);
                     }
                 }
+                ":n" | ":numerals" => {
+                    if parts.len() > 1 {
+                        match parts[1].parse::<NumeralEncoding>() {
+                            Ok(numerals) => {
+                                args.numerals = numerals;
+                                println!("Numerals: {}", args.numerals);
+                            }
+                            Err(err) => println!("Error: {}", err),
+                        }
+                    } else {
+                        println!("Current numerals: {}", args.numerals);
+                    }
+                }
+                ":s" | ":strategy" => {
+                    if parts.len() > 1 {
+                        match parts[1].parse::<Strategy>() {
+                            Ok(strategy) => {
+                                args.strategy = strategy;
+                                println!("Strategy: {}", args.strategy);
+                            }
+                            Err(err) => println!("Error: {}", err),
+                        }
+                    } else {
+                        println!("Current strategy: {}", args.strategy);
+                    }
+                }
                 _ => println!(
                     "Unknown command: {}. Type :help for available commands.",
                     command
@@ -77,14 +491,37 @@ fn main() {
             continue;
         }
 
-        let expr = parse(&input);
-        expr.map(|e| {
-            let result = trace_eval(bind_vars(*e), args.trace, args.unique_id, args.print_step);
-            println!("{}", result.0.format(args.unique_id));
-            if result.1 {
-                println!("...");
+        if system_f {
+            match parse_systemf_statement(&input) {
+                Ok(Statement::Define(name, term)) => {
+                    match type_of_with(&term, &env.system_f_types()) {
+                        Ok(ty) => {
+                            println!("Defined {} : {}", name, ty);
+                            let erased = bind_vars(erase(&term), &mut NameSupply::new());
+                            env.define_typed(name, erased, ty);
+                        }
+                        Err(err) => println!("Error: {}", err),
+                    }
+                }
+                Ok(Statement::Eval(term)) => match type_of_with(&term, &env.system_f_types()) {
+                    Ok(ty) => {
+                        evaluate(erase(&term), &env, &args, true);
+                        println!(":: {}", ty);
+                    }
+                    Err(err) => println!("Error: {}", err),
+                },
+                Err(err) => println!("Error: {}", err),
+            }
+            continue;
+        }
+
+        match parse_statement(&input, args.parse_options()) {
+            Ok(Statement::Define(name, e)) => {
+                env.define(name.clone(), bind_vars(*e, &mut NameSupply::new()));
+                println!("Defined {}", name);
             }
-        })
-        .unwrap_or_else(|err| println!("Error: {}", err));
+            Ok(Statement::Eval(e)) => evaluate(*e, &env, &args, false),
+            Err(err) => println!("Error: {}", err),
+        }
     }
 }
@@ -0,0 +1,206 @@
+//! Parigot encodings, where a numeral holds both its predecessor and its fold
+//!
+//! `n + 1 = λs.λz.s n (n s z)`, so `PRED` takes a single step like Scott
+//! numerals while iteration needs no recursion like Church numerals. The price
+//! is size: a numeral contains its predecessor twice.
+
+use crate::church::{church_succ, church_y, church_zero};
+use crate::expr::{app, fun, var, Expr};
+use crate::scott::{scott_succ, scott_zero};
+
+pub fn parigot_zero() -> Box<Expr> {
+    // λs.λz.z
+    fun("s".to_string(), fun("z".to_string(), var("z".to_string())))
+}
+
+/// The Parigot numeral `n` in normal form
+///
+/// Each numeral holds its predecessor both as itself and folded, so the term
+/// doubles in size with every increment; the parser only accepts literals up
+/// to [`crate::parser::NumeralEncoding::max_literal`].
+pub fn parigot_numeral(n: u64) -> Box<Expr> {
+    // n + 1  =  λs.λz.s n (n s z), with n s z already reduced
+    let mut body = var("z".to_string());
+    let mut result = parigot_zero();
+    for _ in 0..n {
+        body = app(app(var("s".to_string()), result), body);
+        result = fun("s".to_string(), fun("z".to_string(), body.clone()));
+    }
+    result
+}
+
+pub fn parigot_succ() -> Box<Expr> {
+    // λn.λs.λz.s n (n s z)
+    fun(
+        "n".to_string(),
+        fun(
+            "s".to_string(),
+            fun(
+                "z".to_string(),
+                app(
+                    app(var("s".to_string()), var("n".to_string())),
+                    app(
+                        app(var("n".to_string()), var("s".to_string())),
+                        var("z".to_string()),
+                    ),
+                ),
+            ),
+        ),
+    )
+}
+
+pub fn parigot_pred() -> Box<Expr> {
+    // λn.n (λp.λr.p) 0
+    fun(
+        "n".to_string(),
+        app(
+            app(
+                var("n".to_string()),
+                fun("p".to_string(), fun("r".to_string(), var("p".to_string()))),
+            ),
+            parigot_zero(),
+        ),
+    )
+}
+
+pub fn parigot_case() -> Box<Expr> {
+    // λn.λz.λs.n (λp.λr.s p) z  =>  z when n is 0, s p when n is p + 1
+    fun(
+        "n".to_string(),
+        fun(
+            "z".to_string(),
+            fun(
+                "s".to_string(),
+                app(
+                    app(
+                        var("n".to_string()),
+                        fun(
+                            "p".to_string(),
+                            fun(
+                                "r".to_string(),
+                                app(var("s".to_string()), var("p".to_string())),
+                            ),
+                        ),
+                    ),
+                    var("z".to_string()),
+                ),
+            ),
+        ),
+    )
+}
+
+pub fn church_to_parigot() -> Box<Expr> {
+    // λn.n SUCC 0
+    fun(
+        "n".to_string(),
+        app(app(var("n".to_string()), parigot_succ()), parigot_zero()),
+    )
+}
+
+pub fn parigot_to_church() -> Box<Expr> {
+    // λn.n (λp.SUCC) 0
+    fun(
+        "n".to_string(),
+        app(
+            app(var("n".to_string()), fun("p".to_string(), church_succ())),
+            church_zero(),
+        ),
+    )
+}
+
+pub fn parigot_to_scott() -> Box<Expr> {
+    // λn.n (λp.SUCC) 0
+    fun(
+        "n".to_string(),
+        app(
+            app(var("n".to_string()), fun("p".to_string(), scott_succ())),
+            scott_zero(),
+        ),
+    )
+}
+
+pub fn scott_to_parigot() -> Box<Expr> {
+    // Y (λr.λn.n (λp.SUCC (r p)) 0)
+    app(
+        church_y(),
+        fun(
+            "r".to_string(),
+            fun(
+                "n".to_string(),
+                app(
+                    app(
+                        var("n".to_string()),
+                        fun(
+                            "p".to_string(),
+                            app(
+                                parigot_succ(),
+                                app(var("r".to_string()), var("p".to_string())),
+                            ),
+                        ),
+                    ),
+                    parigot_zero(),
+                ),
+            ),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::church::church_numeral;
+    use crate::scott::scott_numeral;
+    use crate::test_support::eval_to_normal;
+
+    #[test]
+    fn test_numeral() {
+        assert_eq!(parigot_numeral(0), parigot_zero());
+        assert_eq!(
+            parigot_numeral(2).format(false),
+            "(\\s.(\\z.((s (\\s.(\\z.((s (\\s.(\\z.z))) z)))) ((s (\\s.(\\z.z))) z))))"
+        );
+    }
+
+    #[test]
+    fn test_succ_pred() {
+        let result = eval_to_normal(*app(parigot_succ(), parigot_numeral(2)));
+        assert!(
+            result.alpha_eq(&parigot_numeral(3)),
+            "SUCC 2 should equal 3"
+        );
+        let result = eval_to_normal(*app(parigot_pred(), parigot_numeral(3)));
+        assert!(
+            result.alpha_eq(&parigot_numeral(2)),
+            "PRED 3 should equal 2"
+        );
+        let result = eval_to_normal(*app(parigot_pred(), parigot_zero()));
+        assert!(result.alpha_eq(&parigot_zero()), "PRED 0 should equal 0");
+    }
+
+    #[test]
+    fn test_case() {
+        let case = |n| {
+            eval_to_normal(*app(
+                app(
+                    app(parigot_case(), parigot_numeral(n)),
+                    var("z".to_string()),
+                ),
+                var("s".to_string()),
+            ))
+        };
+        assert!(case(0).alpha_eq(&var("z".to_string())));
+        assert!(case(2).alpha_eq(&app(var("s".to_string()), parigot_numeral(1))));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let result = eval_to_normal(*app(church_to_parigot(), church_numeral(3)));
+        assert!(result.alpha_eq(&parigot_numeral(3)));
+        let result = eval_to_normal(*app(parigot_to_church(), parigot_numeral(3)));
+        assert!(result.alpha_eq(&church_numeral(3)));
+        let result = eval_to_normal(*app(parigot_to_scott(), parigot_numeral(3)));
+        assert!(result.alpha_eq(&scott_numeral(3)));
+        let result = eval_to_normal(*app(scott_to_parigot(), scott_numeral(3)));
+        assert!(result.alpha_eq(&parigot_numeral(3)));
+    }
+}
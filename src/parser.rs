@@ -1,13 +1,115 @@
-use crate::expr::{app, fun, var, Expr};
+use crate::binary::binary_numeral;
+use crate::church::church_numeral;
+use crate::expr::{app, var, Expr, PrimOp, VarName};
+use crate::parigot::parigot_numeral;
+use crate::scott::scott_numeral;
+use crate::systemf::{self, Term};
+use crate::types::Type;
 use anyhow::Result;
 
 #[derive(Debug, PartialEq)]
 enum Token {
-    Lambda, // \
-    Dot,    // .
-    OParen, // (
-    CParen, // )
+    Lambda,   // \
+    Dot,      // .
+    OParen,   // (
+    CParen,   // )
+    Equals,   // =
+    Colon,    // :
+    Arrow,    // ->
+    TyLambda, // /\
+    OBracket, // [
+    CBracket, // ]
+    Forall,   // ∀
     Name(String),
+    Number(u64),
+}
+
+/// Encoding that integer literals desugar to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum NumeralEncoding {
+    /// `λf.λx.f (f ... x)`
+    #[default]
+    Church,
+    /// `λs.λz.s n-1`
+    Scott,
+    /// `λs.λz.s n-1 (n-1 s z)`
+    Parigot,
+    /// Little-endian bits, see [`crate::binary`]
+    Binary,
+}
+
+impl NumeralEncoding {
+    /// Largest literal accepted in this encoding
+    ///
+    /// Church and Scott numerals nest one level deeper per unit, and the
+    /// evaluator walks terms recursively, so bigger literals would overflow
+    /// the stack. A Parigot numeral holds its predecessor twice and doubles
+    /// in size with each unit.
+    pub fn max_literal(self) -> u64 {
+        match self {
+            NumeralEncoding::Church => 1000,
+            NumeralEncoding::Scott => 250,
+            NumeralEncoding::Parigot => 12,
+            NumeralEncoding::Binary => u64::MAX,
+        }
+    }
+
+    pub fn encode(self, n: u64) -> Box<Expr> {
+        match self {
+            NumeralEncoding::Church => church_numeral(n),
+            NumeralEncoding::Scott => scott_numeral(n),
+            NumeralEncoding::Parigot => parigot_numeral(n),
+            NumeralEncoding::Binary => binary_numeral(n),
+        }
+    }
+}
+
+impl std::fmt::Display for NumeralEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            NumeralEncoding::Church => write!(f, "church"),
+            NumeralEncoding::Scott => write!(f, "scott"),
+            NumeralEncoding::Parigot => write!(f, "parigot"),
+            NumeralEncoding::Binary => write!(f, "binary"),
+        }
+    }
+}
+
+impl std::str::FromStr for NumeralEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "church" => Ok(NumeralEncoding::Church),
+            "scott" => Ok(NumeralEncoding::Scott),
+            "parigot" => Ok(NumeralEncoding::Parigot),
+            "binary" => Ok(NumeralEncoding::Binary),
+            _ => Err(anyhow::anyhow!(
+                "unknown numeral encoding: {} (expected church, scott, parigot or binary)",
+                s
+            )),
+        }
+    }
+}
+
+/// Options that change how the parser reads its input
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// What integer literals such as `42` desugar to
+    pub numerals: NumeralEncoding,
+    /// Read literals as native integers and `add`, `sub`, `mul`, `eq` and
+    /// `ifz` as primitive operations instead of variables
+    pub primitives: bool,
+}
+
+/// A line of input: either a definition or an expression to evaluate
+///
+/// The expressions are untyped terms by default and [`Term`]s in System F.
+#[derive(Debug, PartialEq)]
+pub enum Statement<E = Box<Expr>> {
+    /// `name = expr` or `let name = expr`
+    Define(String, E),
+    Eval(E),
 }
 
 struct Lexer {
@@ -48,7 +150,7 @@ impl Lexer {
     fn read_name(&mut self) -> String {
         let mut ret = String::new();
         while let Some(ch) = self.current() {
-            if ch.is_ascii_alphanumeric() || ch == '_' {
+            if ch.is_ascii_alphanumeric() || ch == '_' || ch == '\'' {
                 ret.push(ch);
                 self.advance();
             } else {
@@ -58,6 +160,35 @@ impl Lexer {
         ret
     }
 
+    fn read_number(&mut self) -> Result<u64> {
+        let mut digits = String::new();
+        while let Some(ch) = self.current() {
+            if ch.is_ascii_digit() {
+                digits.push(ch);
+                self.advance();
+            } else {
+                break;
+            }
+        }
+        if let Some(ch) = self.current()
+            && (ch.is_ascii_alphabetic() || ch == '_')
+        {
+            return Err(anyhow::anyhow!("invalid number literal: {}{}", digits, ch));
+        }
+        digits
+            .parse()
+            .map_err(|_| anyhow::anyhow!("number literal too large: {}", digits))
+    }
+
+    fn skip_comment(&mut self) {
+        while let Some(ch) = self.current() {
+            if ch == '\n' {
+                break;
+            }
+            self.advance();
+        }
+    }
+
     fn tokenize(&mut self) -> Result<Vec<Token>> {
         let mut tokens = Vec::new();
 
@@ -66,6 +197,39 @@ impl Lexer {
 
             match self.current() {
                 None => break,
+                Some('-') if self.input.get(self.pos + 1) == Some(&'-') => {
+                    self.skip_comment();
+                }
+                Some('-') if self.input.get(self.pos + 1) == Some(&'>') => {
+                    tokens.push(Token::Arrow);
+                    self.advance();
+                    self.advance();
+                }
+                Some('→') => {
+                    tokens.push(Token::Arrow);
+                    self.advance();
+                }
+                Some('/') if self.input.get(self.pos + 1) == Some(&'\\') => {
+                    tokens.push(Token::TyLambda);
+                    self.advance();
+                    self.advance();
+                }
+                Some('Λ') => {
+                    tokens.push(Token::TyLambda);
+                    self.advance();
+                }
+                Some('[') => {
+                    tokens.push(Token::OBracket);
+                    self.advance();
+                }
+                Some(']') => {
+                    tokens.push(Token::CBracket);
+                    self.advance();
+                }
+                Some('∀') => {
+                    tokens.push(Token::Forall);
+                    self.advance();
+                }
                 Some('\\') | Some('λ') => {
                     tokens.push(Token::Lambda);
                     self.advance();
@@ -82,10 +246,22 @@ impl Lexer {
                     tokens.push(Token::CParen);
                     self.advance();
                 }
+                Some('=') => {
+                    tokens.push(Token::Equals);
+                    self.advance();
+                }
+                Some(':') => {
+                    tokens.push(Token::Colon);
+                    self.advance();
+                }
                 Some(ch) if ch.is_ascii_alphabetic() || ch == '_' => {
                     let name = self.read_name();
                     tokens.push(Token::Name(name));
                 }
+                Some(ch) if ch.is_ascii_digit() => {
+                    let n = self.read_number()?;
+                    tokens.push(Token::Number(n));
+                }
                 _ => {
                     return Err(anyhow::anyhow!(
                         "unexpected character: {}",
@@ -102,11 +278,16 @@ impl Lexer {
 struct Parser {
     tokens: Vec<Token>,
     pos: usize,
+    options: ParseOptions,
 }
 
 impl Parser {
-    fn new(tokens: Vec<Token>) -> Self {
-        Parser { tokens, pos: 0 }
+    fn new(tokens: Vec<Token>, options: ParseOptions) -> Self {
+        Parser {
+            tokens,
+            pos: 0,
+            options,
+        }
     }
 
     fn current(&self) -> Option<&Token> {
@@ -139,34 +320,92 @@ impl Parser {
         }
     }
 
+    /// Read a name, describing it as `what` if something else comes instead
+    fn expect_name(&mut self, what: &str) -> Result<String> {
+        match self.current() {
+            Some(Token::Name(name)) => {
+                let n = name.clone();
+                self.advance();
+                Ok(n)
+            }
+            Some(tok) => Err(anyhow::anyhow!("expected {}, got {:?}", what, tok)),
+            None => Err(anyhow::anyhow!("expected {}, got end of input", what)),
+        }
+    }
+
     fn parse_lambda(&mut self) -> Result<Box<Expr>> {
         self.expect(Token::Lambda)?;
 
+        let param = self.expect_name("parameter name")?;
+
+        self.check_bindable(&param)?;
+        let ty = if self.current() == Some(&Token::Colon) {
+            self.advance();
+            Some(self.parse_type()?)
+        } else {
+            None
+        };
+        self.expect(Token::Dot)?;
+
+        let body = self.parse_expr()?;
+
+        Ok(Box::new(Expr::Fun(VarName::new_free(param), ty, body)))
+    }
+
+    /// Parse a type: base names, right-associative arrows and `forall a b.T`
+    fn parse_type(&mut self) -> Result<Type> {
+        if matches!(self.current(), Some(Token::Name(kw)) if kw == "forall")
+            || self.current() == Some(&Token::Forall)
+        {
+            self.advance();
+            let mut vars = vec![self.expect_name("type variable")?];
+            while let Some(Token::Name(name)) = self.current() {
+                vars.push(name.clone());
+                self.advance();
+            }
+            self.expect(Token::Dot)?;
+            let body = self.parse_type()?;
+            return Ok(vars
+                .into_iter()
+                .rev()
+                .fold(body, |body, var| Type::forall(var, body)));
+        }
         let param = match self.current() {
             Some(Token::Name(name)) => {
-                let n = name.clone();
+                let ty = Type::base(name.clone());
                 self.advance();
-                n
-            }
-            Some(tok) => {
-                return Err(anyhow::anyhow!("expected parameter name, got {:?}", tok));
+                ty
             }
-            None => {
-                return Err(anyhow::anyhow!("expected parameter name, got end of input"));
+            Some(Token::OParen) => {
+                self.advance();
+                let ty = self.parse_type()?;
+                self.expect(Token::CParen)?;
+                ty
             }
+            Some(tok) => return Err(anyhow::anyhow!("expected type, got {:?}", tok)),
+            None => return Err(anyhow::anyhow!("expected type, got end of input")),
         };
+        if self.current() == Some(&Token::Arrow) {
+            self.advance();
+            return Ok(Type::arrow(param, self.parse_type()?));
+        }
+        Ok(param)
+    }
 
-        self.expect(Token::Dot)?;
-
-        let body = self.parse_expr()?;
-
-        Ok(fun(param, body))
+    /// Reject binding a name that is read as a primitive
+    fn check_bindable(&self, name: &str) -> Result<()> {
+        if self.options.primitives && PrimOp::from_name(name).is_some() {
+            return Err(anyhow::anyhow!("cannot bind primitive {}", name));
+        }
+        Ok(())
     }
 
     fn parse_application(&mut self) -> Result<Box<Expr>> {
         let mut exprs = Vec::new();
 
-        while let Some(Token::Name(_)) | Some(Token::OParen) = self.current() {
+        while let Some(Token::Name(_)) | Some(Token::Number(_)) | Some(Token::OParen) =
+            self.current()
+        {
             exprs.push(self.parse_atom()?);
         }
 
@@ -187,7 +426,29 @@ impl Parser {
             Some(Token::Name(name)) => {
                 let n = name.clone();
                 self.advance();
-                Ok(var(n))
+                match PrimOp::from_name(&n) {
+                    Some(op) if self.options.primitives => Ok(Box::new(Expr::Prim(op))),
+                    _ => Ok(var(n)),
+                }
+            }
+            Some(Token::Number(n)) => {
+                let n = *n;
+                self.advance();
+                if self.options.primitives {
+                    let lit = i64::try_from(n)
+                        .map_err(|_| anyhow::anyhow!("number literal too large: {}", n))?;
+                    return Ok(Box::new(Expr::Lit(lit)));
+                }
+                let encoding = self.options.numerals;
+                if n > encoding.max_literal() {
+                    return Err(anyhow::anyhow!(
+                        "number literal too large for {} numerals: {} (at most {})",
+                        encoding,
+                        n,
+                        encoding.max_literal()
+                    ));
+                }
+                Ok(encoding.encode(n))
             }
             Some(Token::OParen) => {
                 self.advance();
@@ -209,18 +470,218 @@ impl Parser {
 
         Ok(expr)
     }
+
+    /// Consume `name =` or `let name =` at the start of a statement, if present
+    fn parse_definition_name(&mut self) -> Result<Option<String>> {
+        if matches!(self.current(), Some(Token::Name(kw)) if kw == "let")
+            && matches!(self.tokens.get(self.pos + 1), Some(Token::Name(_)))
+            && self.tokens.get(self.pos + 2) == Some(&Token::Equals)
+        {
+            self.advance();
+        }
+
+        if let (Some(Token::Name(name)), Some(Token::Equals)) =
+            (self.current(), self.tokens.get(self.pos + 1))
+        {
+            let name = name.clone();
+            self.check_bindable(&name)?;
+            self.advance();
+            self.advance();
+            return Ok(Some(name));
+        }
+        Ok(None)
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement> {
+        match self.parse_definition_name()? {
+            Some(name) => Ok(Statement::Define(name, self.parse()?)),
+            None => Ok(Statement::Eval(self.parse()?)),
+        }
+    }
+
+    fn parse_f_expr(&mut self) -> Result<Term> {
+        match self.current() {
+            Some(Token::Lambda) => {
+                self.advance();
+                let param = self.expect_name("parameter name")?;
+                if self.current() != Some(&Token::Colon) {
+                    return Err(anyhow::anyhow!(
+                        "parameter {} needs a type annotation in System F",
+                        param
+                    ));
+                }
+                self.advance();
+                let ty = self.parse_type()?;
+                self.expect(Token::Dot)?;
+                Ok(Term::Fun(param, ty, Box::new(self.parse_f_expr()?)))
+            }
+            Some(Token::TyLambda) => {
+                self.advance();
+                let var = self.expect_name("type variable")?;
+                self.expect(Token::Dot)?;
+                Ok(Term::TyFun(var, Box::new(self.parse_f_expr()?)))
+            }
+            _ => self.parse_f_application(),
+        }
+    }
+
+    /// Parse a sequence of atoms and `[T]` type arguments, applied left to right
+    fn parse_f_application(&mut self) -> Result<Term> {
+        let mut result: Option<Term> = None;
+
+        loop {
+            match (self.current(), result) {
+                (Some(Token::OBracket), Some(term)) => {
+                    self.advance();
+                    let ty = self.parse_type()?;
+                    self.expect(Token::CBracket)?;
+                    result = Some(Term::TyApp(Box::new(term), ty));
+                }
+                (Some(Token::Name(_)) | Some(Token::Number(_)) | Some(Token::OParen), prev) => {
+                    let atom = self.parse_f_atom()?;
+                    result = Some(match prev {
+                        Some(term) => Term::App(Box::new(term), Box::new(atom)),
+                        None => atom,
+                    });
+                }
+                (_, prev) => {
+                    result = prev;
+                    break;
+                }
+            }
+        }
+
+        result.ok_or_else(|| anyhow::anyhow!("expected expression"))
+    }
+
+    fn parse_f_atom(&mut self) -> Result<Term> {
+        match self.current() {
+            Some(Token::Name(name)) => {
+                let n = name.clone();
+                self.advance();
+                Ok(Term::Var(n))
+            }
+            Some(Token::Number(n)) => {
+                let n = *n;
+                self.advance();
+                let max = NumeralEncoding::Church.max_literal();
+                if n > max {
+                    return Err(anyhow::anyhow!(
+                        "number literal too large: {} (at most {})",
+                        n,
+                        max
+                    ));
+                }
+                Ok(systemf::numeral(n))
+            }
+            Some(Token::OParen) => {
+                self.advance();
+                let term = self.parse_f_expr()?;
+                self.expect(Token::CParen)?;
+                Ok(term)
+            }
+            Some(tok) => Err(anyhow::anyhow!("expected identifier or '(', got {:?}", tok)),
+            None => Err(anyhow::anyhow!("unexpected end of input")),
+        }
+    }
+
+    fn parse_f(&mut self) -> Result<Term> {
+        let term = self.parse_f_expr()?;
+
+        if self.current().is_some() {
+            return Err(anyhow::anyhow!("unexpected tokens after expression"));
+        }
+
+        Ok(term)
+    }
 }
 
 pub fn parse(input: &str) -> Result<Box<Expr>> {
+    parse_with(input, ParseOptions::default())
+}
+
+/// Parse an expression with non-default options
+pub fn parse_with(input: &str, options: ParseOptions) -> Result<Box<Expr>> {
     let mut lexer = Lexer::new(input);
     let tokens = lexer.tokenize()?;
-    let mut parser = Parser::new(tokens);
+    let mut parser = Parser::new(tokens, options);
     parser.parse()
 }
 
+/// Parse a type such as `(Nat->Nat)->Nat`
+pub fn parse_type(input: &str) -> Result<Type> {
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens, ParseOptions::default());
+    let ty = parser.parse_type()?;
+    if parser.current().is_some() {
+        return Err(anyhow::anyhow!("unexpected tokens after type"));
+    }
+    Ok(ty)
+}
+
+/// Parse a System F term, where literals are typed Church numerals
+pub fn parse_systemf(input: &str) -> Result<Term> {
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens, ParseOptions::default());
+    parser.parse_f()
+}
+
+/// Parse a System F definition or term
+pub fn parse_systemf_statement(input: &str) -> Result<Statement<Term>> {
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens, ParseOptions::default());
+    match parser.parse_definition_name()? {
+        Some(name) => Ok(Statement::Define(name, parser.parse_f()?)),
+        None => Ok(Statement::Eval(parser.parse_f()?)),
+    }
+}
+
+/// Parse a definition (`name = expr`, `let name = expr`) or a plain expression
+pub fn parse_statement(input: &str, options: ParseOptions) -> Result<Statement> {
+    let mut lexer = Lexer::new(input);
+    let tokens = lexer.tokenize()?;
+    let mut parser = Parser::new(tokens, options);
+    parser.parse_statement()
+}
+
+/// Parse a program made of several statements
+///
+/// Each statement starts on a new line; lines that begin with whitespace
+/// continue the previous statement. `--` starts a comment that runs to the
+/// end of the line.
+pub fn parse_program(source: &str, options: ParseOptions) -> Result<Vec<Statement>> {
+    let mut chunks: Vec<(usize, String)> = Vec::new();
+
+    for (index, line) in source.lines().enumerate() {
+        let code = line.split("--").next().unwrap_or("");
+        if code.trim().is_empty() {
+            continue;
+        }
+        match chunks.last_mut() {
+            Some((_, chunk)) if line.starts_with(char::is_whitespace) => {
+                chunk.push(' ');
+                chunk.push_str(code);
+            }
+            _ => chunks.push((index + 1, code.to_string())),
+        }
+    }
+
+    chunks
+        .into_iter()
+        .map(|(line, chunk)| {
+            parse_statement(&chunk, options)
+                .map_err(|err| anyhow::anyhow!("line {}: {}", line, err))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::expr::fun;
 
     #[test]
     fn test_parse_variable() {
@@ -336,4 +797,213 @@ mod tests {
         let result = parse("(x");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_parse_statement_definition() {
+        let stmt = parse_statement("ID = \\x.x", ParseOptions::default()).unwrap();
+        assert_eq!(
+            stmt,
+            Statement::Define("ID".to_string(), fun("x".to_string(), var("x".to_string())))
+        );
+        let stmt = parse_statement("let K = \\x.\\y.x", ParseOptions::default()).unwrap();
+        assert_eq!(
+            stmt,
+            Statement::Define(
+                "K".to_string(),
+                fun("x".to_string(), fun("y".to_string(), var("x".to_string())))
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_statement_expression() {
+        let stmt = parse_statement("let x", ParseOptions::default()).unwrap();
+        assert_eq!(
+            stmt,
+            Statement::Eval(app(var("let".to_string()), var("x".to_string())))
+        );
+    }
+
+    #[test]
+    fn test_parse_statement_errors() {
+        assert!(parse_statement("x =", ParseOptions::default()).is_err());
+        assert!(parse_statement("= x", ParseOptions::default()).is_err());
+        assert!(parse_statement("f x = y", ParseOptions::default()).is_err());
+        assert!(parse("x = y").is_err());
+    }
+
+    #[test]
+    fn test_parse_comment() {
+        let expr = parse("f x -- apply f").unwrap();
+        assert_eq!(expr, app(var("f".to_string()), var("x".to_string())));
+    }
+
+    #[test]
+    fn test_parse_program() {
+        let source = "-- identity\nID = \\x.x\n\nK = \\x.\n    \\y.x  -- continued\nK ID\n";
+        let program = parse_program(source, ParseOptions::default()).unwrap();
+        assert_eq!(
+            program,
+            vec![
+                Statement::Define("ID".to_string(), fun("x".to_string(), var("x".to_string()))),
+                Statement::Define(
+                    "K".to_string(),
+                    fun("x".to_string(), fun("y".to_string(), var("x".to_string())))
+                ),
+                Statement::Eval(app(var("K".to_string()), var("ID".to_string()))),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_program_reports_line() {
+        let err = parse_program("ID = \\x.x\n\nK = \\x x\n", ParseOptions::default()).unwrap_err();
+        assert!(err.to_string().starts_with("line 3:"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_church_literal() {
+        let expr = parse("2").unwrap();
+        assert_eq!(expr, church_numeral(2));
+        let expr = parse("f 0").unwrap();
+        assert_eq!(expr, app(var("f".to_string()), church_numeral(0)));
+    }
+
+    #[test]
+    fn test_large_literals_are_rejected() {
+        assert!(parse("1000").is_ok());
+        let err = parse("2000").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "number literal too large for church numerals: 2000 (at most 1000)"
+        );
+        assert!(parse("100000").is_err());
+        let scott = ParseOptions {
+            numerals: NumeralEncoding::Scott,
+            ..Default::default()
+        };
+        assert!(parse_with("251", scott).is_err());
+        let parigot = ParseOptions {
+            numerals: NumeralEncoding::Parigot,
+            ..Default::default()
+        };
+        assert!(parse_with("12", parigot).is_ok());
+        assert!(parse_with("22", parigot).is_err());
+        let binary = ParseOptions {
+            numerals: NumeralEncoding::Binary,
+            ..Default::default()
+        };
+        assert!(parse_with("100000", binary).is_ok());
+        assert!(parse_systemf("2000").is_err());
+    }
+
+    #[test]
+    fn test_parse_literal_encodings() {
+        let scott = ParseOptions {
+            numerals: NumeralEncoding::Scott,
+            ..Default::default()
+        };
+        assert_eq!(parse_with("3", scott).unwrap(), scott_numeral(3));
+        let parigot = ParseOptions {
+            numerals: NumeralEncoding::Parigot,
+            ..Default::default()
+        };
+        assert_eq!(parse_with("2", parigot).unwrap(), parigot_numeral(2));
+        let binary = ParseOptions {
+            numerals: NumeralEncoding::Binary,
+            ..Default::default()
+        };
+        assert_eq!(parse_with("6", binary).unwrap(), binary_numeral(6));
+    }
+
+    #[test]
+    fn test_parse_primitives() {
+        let options = ParseOptions {
+            primitives: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            parse_with("add 2 x", options).unwrap(),
+            app(
+                app(Box::new(Expr::Prim(PrimOp::Add)), Box::new(Expr::Lit(2))),
+                var("x".to_string())
+            )
+        );
+        // Without the flag they are ordinary names and numerals
+        assert_eq!(
+            parse("add 2").unwrap(),
+            app(var("add".to_string()), church_numeral(2))
+        );
+        assert!(parse_with("\\mul.mul", options).is_err());
+        assert!(parse_statement("eq = 1", options).is_err());
+        assert!(parse_with("9223372036854775808", options).is_err());
+    }
+
+    #[test]
+    fn test_parse_literal_errors() {
+        assert!(parse("2x").is_err());
+        assert!(parse("99999999999999999999999").is_err());
+        assert!("octal".parse::<NumeralEncoding>().is_err());
+    }
+
+    #[test]
+    fn test_parse_annotated_lambda() {
+        let expr = parse("\\f:Nat->Nat. \\x : Nat. f x").unwrap();
+        match *expr {
+            Expr::Fun(_, Some(ref ty), _) => {
+                assert_eq!(ty, &Type::arrow(Type::base("Nat"), Type::base("Nat")))
+            }
+            _ => panic!("Expected an annotated abstraction"),
+        }
+        assert_eq!(expr.format(false), "(\\f:Nat->Nat.(\\x:Nat.(f x)))");
+        assert_eq!(parse(&expr.format(false)).unwrap(), expr);
+        assert!(parse("\\x:.x").is_err());
+        assert!(parse("\\x:Nat->.x").is_err());
+    }
+
+    #[test]
+    fn test_parse_type() {
+        assert_eq!(
+            parse_type("A -> B -> C").unwrap(),
+            Type::arrow(
+                Type::base("A"),
+                Type::arrow(Type::base("B"), Type::base("C"))
+            )
+        );
+        assert_eq!(parse_type("(A → B) → C").unwrap().to_string(), "(A->B)->C");
+        assert!(parse_type("(A -> B").is_err());
+        assert!(parse_type("A B").is_err());
+    }
+
+    #[test]
+    fn test_parse_forall_type() {
+        let nat = parse_type("forall a.(a->a)->a->a").unwrap();
+        assert_eq!(nat, systemf::nat_type());
+        assert_eq!(parse_type("∀a.(a → a) → a → a").unwrap(), nat);
+        assert_eq!(
+            parse_type("forall a b.a->b").unwrap(),
+            Type::forall("a", Type::forall("b", parse_type("a->b").unwrap()))
+        );
+        assert_eq!(
+            parse_type("(forall a.a)->B").unwrap().to_string(),
+            "(forall a.a)->B"
+        );
+        assert!(parse_type("forall .a").is_err());
+    }
+
+    #[test]
+    fn test_parse_systemf() {
+        let term = parse_systemf("/\\a.\\x:a.x").unwrap();
+        assert_eq!(term.to_string(), "(/\\a.(\\x:a.x))");
+        let term = parse_systemf("Λb.id [b->b] (id [b]) y").unwrap();
+        assert_eq!(term.to_string(), "(/\\b.(((id [b->b]) (id [b])) y))");
+        assert_eq!(parse_systemf("2").unwrap(), systemf::numeral(2));
+        assert!(parse_systemf("\\x.x").is_err());
+        assert!(parse_systemf("[Nat] x").is_err());
+        assert!(parse_systemf("x [Nat").is_err());
+        assert_eq!(
+            parse_systemf_statement("let ID = /\\a.\\x:a.x").unwrap(),
+            Statement::Define("ID".to_string(), parse_systemf("/\\a.\\x:a.x").unwrap())
+        );
+    }
 }
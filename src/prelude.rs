@@ -0,0 +1,202 @@
+//! Standard definitions built from the Church encodings
+//!
+//! The REPL starts with these names bound unless `--no-prelude` is given.
+
+use crate::binary::*;
+use crate::church::list::{self, ListEncoding};
+use crate::church::*;
+use crate::env::Env;
+use crate::eval::{bind_vars, NameSupply};
+use crate::expr::{fun, var, Expr};
+use crate::parigot::*;
+use crate::scott::*;
+use crate::signed::*;
+
+/// Name and term of every prelude definition
+pub fn definitions() -> Vec<(&'static str, Box<Expr>)> {
+    let p = || var("p".to_string());
+    let q = || var("q".to_string());
+    vec![
+        ("TRUE", church_true()),
+        ("FALSE", church_false()),
+        (
+            "IF",
+            fun(
+                "c".to_string(),
+                fun(
+                    "t".to_string(),
+                    fun(
+                        "e".to_string(),
+                        church_if(
+                            var("c".to_string()),
+                            var("t".to_string()),
+                            var("e".to_string()),
+                        ),
+                    ),
+                ),
+            ),
+        ),
+        ("NOT", fun("p".to_string(), church_not(p()))),
+        (
+            "AND",
+            fun("p".to_string(), fun("q".to_string(), church_and(p(), q()))),
+        ),
+        (
+            "OR",
+            fun("p".to_string(), fun("q".to_string(), church_or(p(), q()))),
+        ),
+        (
+            "XOR",
+            fun("p".to_string(), fun("q".to_string(), church_xor(p(), q()))),
+        ),
+        ("ZERO", church_zero()),
+        ("ONE", church_one()),
+        ("TWO", church_numeral(2)),
+        ("THREE", church_numeral(3)),
+        ("SUCC", church_succ()),
+        ("PRED", church_pred()),
+        ("ADD", church_add()),
+        ("PLUS", church_add()),
+        ("SUB", church_sub()),
+        ("MULT", church_mult()),
+        ("EXP", church_exp()),
+        ("IS_ZERO", church_is_zero()),
+        ("LEQ", church_leq()),
+        ("EQ", church_eq()),
+        ("PAIR", church_pair()),
+        ("FST", church_fst()),
+        ("SND", church_snd()),
+        ("Y", church_y()),
+        ("Z", church_z()),
+        ("NIL", list::nil(ListEncoding::Church)),
+        ("CONS", list::cons(ListEncoding::Church)),
+        ("HEAD", list::head(ListEncoding::Church)),
+        ("TAIL", list::tail(ListEncoding::Church)),
+        ("IS_NIL", list::is_nil(ListEncoding::Church)),
+        ("FOLD", list::fold(ListEncoding::Church)),
+        ("MAP", list::map(ListEncoding::Church)),
+        ("FILTER", list::filter(ListEncoding::Church)),
+        ("LENGTH", list::length(ListEncoding::Church)),
+        ("APPEND", list::append(ListEncoding::Church)),
+        ("RANGE", list::range(ListEncoding::Church)),
+        ("SCOTT_SUCC", scott_succ()),
+        ("SCOTT_PRED", scott_pred()),
+        ("SCOTT_CASE", scott_case()),
+        ("PARIGOT_SUCC", parigot_succ()),
+        ("PARIGOT_PRED", parigot_pred()),
+        ("PARIGOT_CASE", parigot_case()),
+        ("CHURCH_TO_SCOTT", church_to_scott()),
+        ("SCOTT_TO_CHURCH", scott_to_church()),
+        ("CHURCH_TO_PARIGOT", church_to_parigot()),
+        ("PARIGOT_TO_CHURCH", parigot_to_church()),
+        ("SCOTT_TO_PARIGOT", scott_to_parigot()),
+        ("PARIGOT_TO_SCOTT", parigot_to_scott()),
+        ("BINARY_SUCC", binary_succ()),
+        ("BINARY_ADD", binary_add()),
+        ("BINARY_MULT", binary_mult()),
+        ("BINARY_COMPARE", binary_compare()),
+        ("BINARY_LEQ", binary_leq()),
+        ("BINARY_EQ", binary_eq()),
+        ("SIGNED_FROM_NAT", signed_from_nat()),
+        ("SIGNED_NEG", signed_neg()),
+        ("SIGNED_ADD", signed_add()),
+        ("SIGNED_SUB", signed_sub()),
+        ("SIGNED_MULT", signed_mult()),
+        ("SIGNED_LEQ", signed_leq()),
+        ("SIGNED_EQ", signed_eq()),
+    ]
+}
+
+/// Create an environment holding the prelude definitions
+pub fn prelude() -> Env {
+    let mut env = Env::new();
+    for (name, expr) in definitions() {
+        env.define(name.to_string(), bind_vars(*expr, &mut NameSupply::new()));
+    }
+    env
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::{equivalent, Equivalence};
+    use crate::parser::parse;
+
+    fn check(input: &str, expected: &str) -> Equivalence {
+        let env = prelude();
+        let mut supply = NameSupply::new();
+        let lhs = bind_vars(*parse(input).unwrap(), &mut supply);
+        let lhs = env.expand(lhs, &mut supply);
+        let rhs = bind_vars(*parse(expected).unwrap(), &mut supply);
+        let rhs = env.expand(rhs, &mut supply);
+        equivalent(&lhs, &rhs, 1000, false)
+    }
+
+    #[test]
+    fn test_prelude_arithmetic() {
+        assert_eq!(
+            check("ADD TWO THREE", "SUCC (SUCC THREE)"),
+            Equivalence::Equal
+        );
+        assert_eq!(
+            check("MULT TWO THREE", "ADD THREE THREE"),
+            Equivalence::Equal
+        );
+        assert_eq!(check("SUB THREE ONE", "TWO"), Equivalence::Equal);
+        assert_eq!(check("PRED ONE", "ZERO"), Equivalence::Equal);
+        assert_eq!(
+            check("EXP TWO THREE", "MULT TWO (ADD TWO TWO)"),
+            Equivalence::Equal
+        );
+        assert_eq!(check("EQ (ADD ONE TWO) THREE", "TRUE"), Equivalence::Equal);
+    }
+
+    #[test]
+    fn test_prelude_booleans() {
+        assert_eq!(check("IF TRUE a b", "a"), Equivalence::Equal);
+        assert_eq!(check("OR FALSE TRUE", "TRUE"), Equivalence::Equal);
+        assert_eq!(check("AND TRUE FALSE", "FALSE"), Equivalence::Equal);
+        assert_eq!(check("NOT (IS_ZERO ONE)", "TRUE"), Equivalence::Equal);
+        assert_eq!(check("XOR TRUE TRUE", "FALSE"), Equivalence::Equal);
+        assert_eq!(check("SND (PAIR a b)", "b"), Equivalence::Equal);
+    }
+
+    #[test]
+    fn test_prelude_lists() {
+        assert_eq!(
+            check("MAP SUCC (RANGE TWO)", "CONS ONE (CONS TWO NIL)"),
+            Equivalence::Equal
+        );
+        assert_eq!(
+            check("LENGTH (APPEND (RANGE TWO) (RANGE THREE))", "ADD TWO THREE"),
+            Equivalence::Equal
+        );
+    }
+
+    #[test]
+    fn test_prelude_conversions() {
+        assert_eq!(
+            check(
+                "SCOTT_TO_CHURCH (SCOTT_PRED (CHURCH_TO_SCOTT THREE))",
+                "TWO"
+            ),
+            Equivalence::Equal
+        );
+        assert_eq!(
+            check(
+                "PARIGOT_TO_CHURCH (PARIGOT_SUCC (CHURCH_TO_PARIGOT TWO))",
+                "THREE"
+            ),
+            Equivalence::Equal
+        );
+    }
+
+    #[test]
+    fn test_prelude_definitions_are_closed() {
+        for (name, expr) in prelude().iter() {
+            let mut names = std::collections::HashSet::new();
+            crate::eval::free_names(expr, &mut Vec::new(), &mut names);
+            assert!(names.is_empty(), "{} has free variables {:?}", name, names);
+        }
+    }
+}
@@ -0,0 +1,148 @@
+//! Scott encodings, where a numeral holds its predecessor directly
+
+use crate::church::{church_succ, church_y, church_zero};
+use crate::expr::{app, fun, var, Expr};
+
+pub fn scott_zero() -> Box<Expr> {
+    // λs.λz.z
+    fun("s".to_string(), fun("z".to_string(), var("z".to_string())))
+}
+
+pub fn scott_numeral(n: u64) -> Box<Expr> {
+    // n + 1  =  λs.λz.s n
+    let mut result = scott_zero();
+    for _ in 0..n {
+        result = fun(
+            "s".to_string(),
+            fun("z".to_string(), app(var("s".to_string()), result)),
+        );
+    }
+    result
+}
+
+pub fn scott_succ() -> Box<Expr> {
+    // λn.λs.λz.s n
+    fun(
+        "n".to_string(),
+        fun(
+            "s".to_string(),
+            fun(
+                "z".to_string(),
+                app(var("s".to_string()), var("n".to_string())),
+            ),
+        ),
+    )
+}
+
+pub fn scott_pred() -> Box<Expr> {
+    // λn.n (λp.p) 0  =>  a single step, unlike Church PRED
+    fun(
+        "n".to_string(),
+        app(
+            app(
+                var("n".to_string()),
+                fun("p".to_string(), var("p".to_string())),
+            ),
+            scott_zero(),
+        ),
+    )
+}
+
+pub fn scott_case() -> Box<Expr> {
+    // λn.λz.λs.n s z  =>  z when n is 0, s p when n is p + 1
+    fun(
+        "n".to_string(),
+        fun(
+            "z".to_string(),
+            fun(
+                "s".to_string(),
+                app(
+                    app(var("n".to_string()), var("s".to_string())),
+                    var("z".to_string()),
+                ),
+            ),
+        ),
+    )
+}
+
+pub fn church_to_scott() -> Box<Expr> {
+    // λn.n SUCC 0
+    fun(
+        "n".to_string(),
+        app(app(var("n".to_string()), scott_succ()), scott_zero()),
+    )
+}
+
+pub fn scott_to_church() -> Box<Expr> {
+    // Y (λr.λn.n (λp.SUCC (r p)) 0)
+    //
+    // A Scott numeral cannot iterate on its own, so this needs recursion.
+    app(
+        church_y(),
+        fun(
+            "r".to_string(),
+            fun(
+                "n".to_string(),
+                app(
+                    app(
+                        var("n".to_string()),
+                        fun(
+                            "p".to_string(),
+                            app(
+                                church_succ(),
+                                app(var("r".to_string()), var("p".to_string())),
+                            ),
+                        ),
+                    ),
+                    church_zero(),
+                ),
+            ),
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::church::church_numeral;
+    use crate::test_support::eval_to_normal;
+
+    #[test]
+    fn test_numeral() {
+        assert_eq!(scott_numeral(0), scott_zero());
+        assert_eq!(
+            scott_numeral(2).format(false),
+            "(\\s.(\\z.(s (\\s.(\\z.(s (\\s.(\\z.z))))))))"
+        );
+    }
+
+    #[test]
+    fn test_succ_pred() {
+        let result = eval_to_normal(*app(scott_succ(), scott_numeral(2)));
+        assert!(result.alpha_eq(&scott_numeral(3)), "SUCC 2 should equal 3");
+        let result = eval_to_normal(*app(scott_pred(), scott_numeral(3)));
+        assert!(result.alpha_eq(&scott_numeral(2)), "PRED 3 should equal 2");
+        let result = eval_to_normal(*app(scott_pred(), scott_zero()));
+        assert!(result.alpha_eq(&scott_zero()), "PRED 0 should equal 0");
+    }
+
+    #[test]
+    fn test_case() {
+        let case = |n| {
+            eval_to_normal(*app(
+                app(app(scott_case(), scott_numeral(n)), var("z".to_string())),
+                var("s".to_string()),
+            ))
+        };
+        assert!(case(0).alpha_eq(&var("z".to_string())));
+        assert!(case(2).alpha_eq(&app(var("s".to_string()), scott_numeral(1))));
+    }
+
+    #[test]
+    fn test_church_conversions() {
+        let result = eval_to_normal(*app(church_to_scott(), church_numeral(3)));
+        assert!(result.alpha_eq(&scott_numeral(3)));
+        let result = eval_to_normal(*app(scott_to_church(), scott_numeral(3)));
+        assert!(result.alpha_eq(&church_numeral(3)));
+    }
+}
@@ -0,0 +1,212 @@
+//! Signed integers as pairs of binary naturals
+//!
+//! The pair `PAIR p n` stands for `p - n`, so one integer has many
+//! representations: `PAIR 3 1` and `PAIR 2 0` are both 2. Operations only
+//! ever add naturals, so nothing is lost to truncated subtraction.
+
+use crate::binary::{binary_add, binary_end, binary_eq, binary_leq, binary_mult, binary_numeral};
+use crate::church::church_pair;
+use crate::expr::{app, fun, var, Expr};
+
+fn pair(first: Box<Expr>, second: Box<Expr>) -> Box<Expr> {
+    app(app(church_pair(), first), second)
+}
+
+/// `x (λa.λb.y (λc.λd.body))`, unpacking both integers
+fn unpack2(body: Box<Expr>) -> Box<Expr> {
+    fun(
+        "x".to_string(),
+        fun(
+            "y".to_string(),
+            app(
+                var("x".to_string()),
+                fun(
+                    "a".to_string(),
+                    fun(
+                        "b".to_string(),
+                        app(
+                            var("y".to_string()),
+                            fun("c".to_string(), fun("d".to_string(), body)),
+                        ),
+                    ),
+                ),
+            ),
+        ),
+    )
+}
+
+fn add(m: Box<Expr>, n: Box<Expr>) -> Box<Expr> {
+    app(app(binary_add(), m), n)
+}
+
+fn mult(m: Box<Expr>, n: Box<Expr>) -> Box<Expr> {
+    app(app(binary_mult(), m), n)
+}
+
+pub fn signed_numeral(n: i64) -> Box<Expr> {
+    // λf.f n 0  or  λf.f 0 n
+    let magnitude = binary_numeral(n.unsigned_abs());
+    let (first, second) = if n < 0 {
+        (binary_end(), magnitude)
+    } else {
+        (magnitude, binary_end())
+    };
+    fun(
+        "f".to_string(),
+        app(app(var("f".to_string()), first), second),
+    )
+}
+
+pub fn signed_from_nat() -> Box<Expr> {
+    // λn.PAIR n 0
+    fun("n".to_string(), pair(var("n".to_string()), binary_end()))
+}
+
+pub fn signed_neg() -> Box<Expr> {
+    // λx.x (λa.λb.PAIR b a)
+    fun(
+        "x".to_string(),
+        app(
+            var("x".to_string()),
+            fun(
+                "a".to_string(),
+                fun(
+                    "b".to_string(),
+                    pair(var("b".to_string()), var("a".to_string())),
+                ),
+            ),
+        ),
+    )
+}
+
+pub fn signed_add() -> Box<Expr> {
+    // λx.λy.(a - b) + (c - d) = (a + c) - (b + d)
+    unpack2(pair(
+        add(var("a".to_string()), var("c".to_string())),
+        add(var("b".to_string()), var("d".to_string())),
+    ))
+}
+
+pub fn signed_sub() -> Box<Expr> {
+    // λx.λy.(a - b) - (c - d) = (a + d) - (b + c)
+    unpack2(pair(
+        add(var("a".to_string()), var("d".to_string())),
+        add(var("b".to_string()), var("c".to_string())),
+    ))
+}
+
+pub fn signed_mult() -> Box<Expr> {
+    // λx.λy.(a - b)(c - d) = (ac + bd) - (ad + bc)
+    unpack2(pair(
+        add(
+            mult(var("a".to_string()), var("c".to_string())),
+            mult(var("b".to_string()), var("d".to_string())),
+        ),
+        add(
+            mult(var("a".to_string()), var("d".to_string())),
+            mult(var("b".to_string()), var("c".to_string())),
+        ),
+    ))
+}
+
+pub fn signed_leq() -> Box<Expr> {
+    // λx.λy.a - b <= c - d  iff  a + d <= c + b
+    unpack2(app(
+        app(
+            binary_leq(),
+            add(var("a".to_string()), var("d".to_string())),
+        ),
+        add(var("c".to_string()), var("b".to_string())),
+    ))
+}
+
+pub fn signed_eq() -> Box<Expr> {
+    // λx.λy.a - b = c - d  iff  a + d = c + b
+    unpack2(app(
+        app(binary_eq(), add(var("a".to_string()), var("d".to_string()))),
+        add(var("c".to_string()), var("b".to_string())),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::{decode_bool, decode_signed};
+    use crate::test_support::eval_to_normal;
+
+    fn apply2(f: Box<Expr>, x: i64, y: i64) -> Expr {
+        eval_to_normal(*app(app(f, signed_numeral(x)), signed_numeral(y)))
+    }
+
+    const CASES: [(i64, i64); 5] = [(0, 0), (3, -5), (-4, -2), (-6, 6), (7, 2)];
+
+    #[test]
+    fn test_numeral_readback() {
+        for n in [0, 5, -5, i64::MIN + 1] {
+            assert_eq!(decode_signed(&signed_numeral(n)), Some(n));
+        }
+    }
+
+    #[test]
+    fn test_neg() {
+        let result = eval_to_normal(*app(signed_neg(), signed_numeral(-3)));
+        assert_eq!(decode_signed(&result), Some(3), "NEG -3 should equal 3");
+    }
+
+    #[test]
+    fn test_add_sub() {
+        for (x, y) in CASES {
+            assert_eq!(
+                decode_signed(&apply2(signed_add(), x, y)),
+                Some(x + y),
+                "ADD {} {}",
+                x,
+                y
+            );
+            assert_eq!(
+                decode_signed(&apply2(signed_sub(), x, y)),
+                Some(x - y),
+                "SUB {} {}",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn test_mult() {
+        for (x, y) in CASES {
+            assert_eq!(
+                decode_signed(&apply2(signed_mult(), x, y)),
+                Some(x * y),
+                "MULT {} {}",
+                x,
+                y
+            );
+        }
+    }
+
+    #[test]
+    fn test_compare() {
+        for (x, y) in CASES {
+            assert_eq!(
+                decode_bool(&apply2(signed_leq(), x, y)),
+                Some(x <= y),
+                "LEQ {} {}",
+                x,
+                y
+            );
+            assert_eq!(
+                decode_bool(&apply2(signed_eq(), x, y)),
+                Some(x == y),
+                "EQ {} {}",
+                x,
+                y
+            );
+        }
+        // 1 - 3 and 0 - 2 are both -2
+        let other = pair(binary_numeral(1), binary_numeral(3));
+        let result = eval_to_normal(*app(app(signed_eq(), other), signed_numeral(-2)));
+        assert_eq!(decode_bool(&result), Some(true));
+    }
+}
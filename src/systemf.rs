@@ -0,0 +1,273 @@
+//! System F, the polymorphic lambda calculus
+//!
+//! Terms abstract over types with `/\a.e` and are instantiated with `e [T]`;
+//! term binders always carry a type. [`type_of`] checks a term and [`erase`]
+//! drops the types, giving an untyped [`Expr`] to evaluate as usual.
+
+use crate::expr::{app, fun, var, Expr};
+use crate::types::{Type, TypeErrorKind};
+use std::collections::HashMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Term {
+    Var(String),
+    /// `\x:T.e`
+    Fun(String, Type, Box<Term>),
+    App(Box<Term>, Box<Term>),
+    /// `/\a.e`
+    TyFun(String, Box<Term>),
+    /// `e [T]`
+    TyApp(Box<Term>, Type),
+}
+
+impl std::fmt::Display for Term {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Term::Var(name) => write!(f, "{}", name),
+            Term::Fun(name, ty, body) => write!(f, "(\\{}:{}.{})", name, ty, body),
+            Term::App(lhs, rhs) => write!(f, "({} {})", lhs, rhs),
+            Term::TyFun(var, body) => write!(f, "(/\\{}.{})", var, body),
+            Term::TyApp(term, ty) => write!(f, "({} [{}])", term, ty),
+        }
+    }
+}
+
+/// `forall a.(a->a)->a->a`, the type of Church numerals
+pub fn nat_type() -> Type {
+    let a = || Type::base("a");
+    Type::forall(
+        "a",
+        Type::arrow(Type::arrow(a(), a()), Type::arrow(a(), a())),
+    )
+}
+
+/// The Church numeral `n` as `/\a.\f:a->a.\x:a.f (... (f x))`
+pub fn numeral(n: u64) -> Term {
+    let a = || Type::base("a");
+    let mut body = Term::Var("x".to_string());
+    for _ in 0..n {
+        body = Term::App(Box::new(Term::Var("f".to_string())), Box::new(body));
+    }
+    Term::TyFun(
+        "a".to_string(),
+        Box::new(Term::Fun(
+            "f".to_string(),
+            Type::arrow(a(), a()),
+            Box::new(Term::Fun("x".to_string(), a(), Box::new(body))),
+        )),
+    )
+}
+
+/// Remove all types from a term
+pub fn erase(term: &Term) -> Expr {
+    match term {
+        Term::Var(name) => *var(name.clone()),
+        Term::Fun(name, _, body) => *fun(name.clone(), Box::new(erase(body))),
+        Term::App(lhs, rhs) => *app(Box::new(erase(lhs)), Box::new(erase(rhs))),
+        Term::TyFun(_, body) | Term::TyApp(body, _) => erase(body),
+    }
+}
+
+/// Replace the free type variable `name` with `ty` in every annotation of `term`
+fn subst_in_term(term: &Term, name: &str, ty: &Type) -> Term {
+    match term {
+        Term::Var(_) => term.clone(),
+        Term::Fun(x, param, body) => Term::Fun(
+            x.clone(),
+            param.subst(name, ty),
+            Box::new(subst_in_term(body, name, ty)),
+        ),
+        Term::App(lhs, rhs) => Term::App(
+            Box::new(subst_in_term(lhs, name, ty)),
+            Box::new(subst_in_term(rhs, name, ty)),
+        ),
+        Term::TyFun(var, _) if var == name => term.clone(),
+        Term::TyFun(var, body) => Term::TyFun(var.clone(), Box::new(subst_in_term(body, name, ty))),
+        Term::TyApp(inner, arg) => Term::TyApp(
+            Box::new(subst_in_term(inner, name, ty)),
+            arg.subst(name, ty),
+        ),
+    }
+}
+
+/// A type error together with the System F subterm it was found at
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeError {
+    pub term: Box<Term>,
+    pub kind: TypeErrorKind,
+}
+
+impl TypeError {
+    fn new(term: &Term, kind: TypeErrorKind) -> Self {
+        TypeError {
+            term: Box::new(term.clone()),
+            kind,
+        }
+    }
+}
+
+impl std::fmt::Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.kind, self.term)
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+/// Compute the type of a closed term
+pub fn type_of(term: &Term) -> Result<Type, TypeError> {
+    type_of_with(term, &HashMap::new())
+}
+
+/// Compute the type of a term whose free variables name `globals`
+pub fn type_of_with(term: &Term, globals: &HashMap<String, Type>) -> Result<Type, TypeError> {
+    check(term, &mut Vec::new(), globals)
+}
+
+fn check(
+    term: &Term,
+    scope: &mut Vec<(String, Type)>,
+    globals: &HashMap<String, Type>,
+) -> Result<Type, TypeError> {
+    match term {
+        Term::Var(name) => scope
+            .iter()
+            .rev()
+            .find(|(bound, _)| bound == name)
+            .map(|(_, ty)| ty)
+            .or_else(|| globals.get(name))
+            .cloned()
+            .ok_or_else(|| TypeError::new(term, TypeErrorKind::Unbound(name.clone()))),
+        Term::Fun(name, param, body) => {
+            scope.push((name.clone(), param.clone()));
+            let result = check(body, scope, globals);
+            scope.pop();
+            Ok(Type::arrow(param.clone(), result?))
+        }
+        Term::App(lhs, rhs) => {
+            let lhs_ty = check(lhs, scope, globals)?;
+            let rhs_ty = check(rhs, scope, globals)?;
+            match lhs_ty {
+                Type::Arrow(param, result) if param.alpha_eq(&rhs_ty) => Ok(*result),
+                Type::Arrow(param, _) => Err(TypeError::new(
+                    rhs,
+                    TypeErrorKind::Mismatch {
+                        expected: *param,
+                        found: rhs_ty,
+                    },
+                )),
+                _ => Err(TypeError::new(lhs, TypeErrorKind::NotAFunction(lhs_ty))),
+            }
+        }
+        Term::TyFun(var, body) => {
+            // A variable that the context already mentions would be captured
+            // by the quantifier, so abstract over a fresh name instead
+            let captures = |name: &str| {
+                scope.iter().any(|(_, ty)| ty.mentions(name))
+                    || globals.values().any(|ty| ty.mentions(name))
+            };
+            if captures(var) {
+                let mut fresh = format!("{}'", var);
+                while captures(&fresh) {
+                    fresh.push('\'');
+                }
+                let renamed = subst_in_term(body, var, &Type::base(fresh.clone()));
+                let body_ty = check(&renamed, scope, globals)?;
+                return Ok(Type::forall(fresh, body_ty));
+            }
+            Ok(Type::forall(var.clone(), check(body, scope, globals)?))
+        }
+        Term::TyApp(inner, arg) => match check(inner, scope, globals)? {
+            Type::Forall(var, body) => Ok(body.subst(&var, arg)),
+            ty => Err(TypeError::new(inner, TypeErrorKind::NotPolymorphic(ty))),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decode::decode_nat;
+    use crate::eval::{bind_vars, trace_eval, NameSupply, Strategy};
+    use crate::parser::{parse_systemf, parse_type};
+
+    fn type_str(input: &str) -> Result<String, String> {
+        type_of(&parse_systemf(input).unwrap())
+            .map(|ty| ty.to_string())
+            .map_err(|err| err.to_string())
+    }
+
+    const SUCC: &str = "\\n:forall a.(a->a)->a->a./\\a.\\f:a->a.\\x:a.f (n [a] f x)";
+
+    #[test]
+    fn test_polymorphic_identity() {
+        assert_eq!(type_str("/\\a.\\x:a.x"), Ok("forall a.a->a".to_string()));
+        assert_eq!(type_str("(/\\a.\\x:a.x) [Nat]"), Ok("Nat->Nat".to_string()));
+        assert_eq!(
+            type_str("(/\\a.\\x:a.x) [forall b.b->b] (/\\b.\\y:b.y)"),
+            Ok("forall b.b->b".to_string())
+        );
+    }
+
+    #[test]
+    fn test_church_numerals() {
+        assert!(type_of(&numeral(3)).unwrap().alpha_eq(&nat_type()));
+        assert_eq!(
+            type_str(SUCC),
+            Ok("(forall a.(a->a)->a->a)->forall a.(a->a)->a->a".to_string())
+        );
+        let succ_two = format!("({}) 2", SUCC);
+        assert_eq!(type_str(&succ_two), Ok(nat_type().to_string()));
+    }
+
+    #[test]
+    fn test_self_application() {
+        assert_eq!(
+            type_str("\\x:forall a.a->a.x [forall a.a->a] x"),
+            Ok("(forall a.a->a)->forall a.a->a".to_string())
+        );
+    }
+
+    #[test]
+    fn test_errors_point_at_subterm() {
+        assert_eq!(
+            type_str("/\\a.\\f:a->a.\\x:a.\\y:Nat.f y"),
+            Err("expected a, found Nat at y".to_string())
+        );
+        assert_eq!(
+            type_str("\\x:Nat.x [Nat]"),
+            Err("cannot apply a term of type Nat to a type at x".to_string())
+        );
+        assert_eq!(
+            type_str("(/\\a.\\x:a.x) (\\y:Nat.y)"),
+            Err("cannot apply a term of type forall a.a->a at (/\\a.(\\x:a.x))".to_string())
+        );
+    }
+
+    #[test]
+    fn test_quantifiers_do_not_capture() {
+        // The inner /\a must not capture the outer a in the type of x
+        let ty = type_of(&parse_systemf("/\\a.\\x:a./\\a.x").unwrap()).unwrap();
+        assert_eq!(ty.to_string(), "forall a.a->forall a'.a");
+        let ty = parse_type("forall b.a->b")
+            .unwrap()
+            .subst("a", &Type::base("b"));
+        assert_eq!(ty.to_string(), "forall b'.b->b'");
+        assert!(parse_type("forall a.a->a")
+            .unwrap()
+            .alpha_eq(&parse_type("forall b.b->b").unwrap()));
+    }
+
+    #[test]
+    fn test_erasure_evaluates() {
+        let term = parse_systemf(&format!("({}) 2", SUCC)).unwrap();
+        let expr = bind_vars(erase(&term), &mut NameSupply::new());
+        let (result, exceeded) = trace_eval(expr, Strategy::NormalOrder, 100, false, false);
+        assert!(!exceeded);
+        assert_eq!(decode_nat(&result), Some(3));
+        assert_eq!(
+            erase(&parse_systemf("(/\\a.\\x:a.x) [Nat] y").unwrap()).format(false),
+            "((\\x.x) y)"
+        );
+    }
+}
@@ -0,0 +1,18 @@
+//! Helpers shared by the unit tests of the encoding modules
+
+use crate::eval::{bind_vars, trace_eval, NameSupply, Strategy};
+use crate::expr::Expr;
+
+/// Reduce a closed term to normal form in normal order, failing the test if
+/// it does not get there within the step limit
+pub(crate) fn eval_to_normal(expr: Expr) -> Expr {
+    let (result, exceeded) = trace_eval(
+        bind_vars(expr, &mut NameSupply::new()),
+        Strategy::NormalOrder,
+        100000,
+        false,
+        false,
+    );
+    assert!(!exceeded, "evaluation did not finish");
+    result
+}
@@ -0,0 +1,330 @@
+//! Simply typed lambda calculus
+//!
+//! Types are base types such as `Nat` plus arrows. Binders carry their type
+//! as an annotation (`\x:Nat->Nat. body`), and [`type_of`] checks a term
+//! against those annotations. Integer literals have the base type `Int`.
+//! Quantified types are only used by [`crate::systemf`].
+
+use crate::expr::{Expr, PrimOp, VarName};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Type {
+    /// A named base type, e.g. `Nat`
+    Base(String),
+    /// `A->B`
+    Arrow(Box<Type>, Box<Type>),
+    /// Type variable, only produced by inference and printed as `a`, `b`, ...
+    Var(usize),
+    /// `forall a.T`, where `a` stands for the base type of that name in `T`
+    Forall(String, Box<Type>),
+}
+
+impl Type {
+    pub fn base(name: impl Into<String>) -> Type {
+        Type::Base(name.into())
+    }
+
+    pub fn arrow(param: Type, result: Type) -> Type {
+        Type::Arrow(Box::new(param), Box::new(result))
+    }
+
+    pub fn forall(var: impl Into<String>, body: Type) -> Type {
+        Type::Forall(var.into(), Box::new(body))
+    }
+
+    /// Type of integer literals
+    pub fn int() -> Type {
+        Type::base("Int")
+    }
+
+    /// Check whether `name` occurs in the type without being quantified
+    pub fn mentions(&self, name: &str) -> bool {
+        match self {
+            Type::Base(base) => base == name,
+            Type::Arrow(param, result) => param.mentions(name) || result.mentions(name),
+            Type::Var(_) => false,
+            Type::Forall(var, body) => var != name && body.mentions(name),
+        }
+    }
+
+    /// Check whether two types are equal up to renaming of quantified variables
+    pub fn alpha_eq(&self, other: &Type) -> bool {
+        fn eq<'a>(a: &'a Type, b: &'a Type, scope: &mut Vec<(&'a str, &'a str)>) -> bool {
+            match (a, b) {
+                (Type::Base(x), Type::Base(y)) => {
+                    match scope.iter().rev().find(|(p, q)| p == x || q == y) {
+                        Some((p, q)) => p == x && q == y,
+                        None => x == y,
+                    }
+                }
+                (Type::Arrow(p1, r1), Type::Arrow(p2, r2)) => {
+                    eq(p1, p2, scope) && eq(r1, r2, scope)
+                }
+                (Type::Var(m), Type::Var(n)) => m == n,
+                (Type::Forall(x, body1), Type::Forall(y, body2)) => {
+                    scope.push((x, y));
+                    let result = eq(body1, body2, scope);
+                    scope.pop();
+                    result
+                }
+                _ => false,
+            }
+        }
+        eq(self, other, &mut Vec::new())
+    }
+
+    /// Replace the unquantified occurrences of `name` with `ty`
+    ///
+    /// Quantifiers that would capture a name used in `ty` are renamed with
+    /// primes first.
+    pub fn subst(&self, name: &str, ty: &Type) -> Type {
+        match self {
+            Type::Base(base) if base == name => ty.clone(),
+            Type::Base(_) | Type::Var(_) => self.clone(),
+            Type::Arrow(param, result) => {
+                Type::arrow(param.subst(name, ty), result.subst(name, ty))
+            }
+            Type::Forall(var, _) if var == name => self.clone(),
+            Type::Forall(var, body) if ty.mentions(var) && body.mentions(name) => {
+                let mut fresh = format!("{}'", var);
+                while ty.mentions(&fresh) || body.mentions(&fresh) {
+                    fresh.push('\'');
+                }
+                let renamed = body.subst(var, &Type::base(fresh.clone()));
+                Type::forall(fresh, renamed.subst(name, ty))
+            }
+            Type::Forall(var, body) => Type::forall(var.clone(), body.subst(name, ty)),
+        }
+    }
+}
+
+impl std::fmt::Display for Type {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Type::Base(name) => write!(f, "{}", name),
+            Type::Var(n) if *n < 26 => write!(f, "{}", (b'a' + *n as u8) as char),
+            Type::Var(n) => write!(f, "{}{}", (b'a' + (*n % 26) as u8) as char, n / 26),
+            Type::Arrow(param, result) => match param.as_ref() {
+                Type::Arrow(_, _) | Type::Forall(_, _) => write!(f, "({})->{}", param, result),
+                _ => write!(f, "{}->{}", param, result),
+            },
+            Type::Forall(var, body) => write!(f, "forall {}.{}", var, body),
+        }
+    }
+}
+
+/// What went wrong while checking a term
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TypeErrorKind {
+    Unbound(String),
+    MissingAnnotation(String),
+    /// The function position has a non-arrow type
+    NotAFunction(Type),
+    /// The argument does not have the parameter's type
+    Mismatch {
+        expected: Type,
+        found: Type,
+    },
+    /// A primitive whose behaviour no simple type describes
+    Untypable(PrimOp),
+    /// Unification would need an infinite type such as `a = a->b`
+    Infinite {
+        var: Type,
+        ty: Type,
+    },
+    /// A type application to a term whose type is not quantified
+    NotPolymorphic(Type),
+}
+
+impl std::fmt::Display for TypeErrorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeErrorKind::Unbound(name) => write!(f, "unbound variable {}", name),
+            TypeErrorKind::MissingAnnotation(name) => {
+                write!(f, "binder {} has no type annotation", name)
+            }
+            TypeErrorKind::NotAFunction(ty) => {
+                write!(f, "cannot apply a term of type {}", ty)
+            }
+            TypeErrorKind::Mismatch { expected, found } => {
+                write!(f, "expected {}, found {}", expected, found)
+            }
+            TypeErrorKind::Untypable(op) => write!(f, "primitive {} has no simple type", op),
+            TypeErrorKind::Infinite { var, ty } => {
+                write!(f, "occurs check: {} occurs in {}", var, ty)
+            }
+            TypeErrorKind::NotPolymorphic(ty) => {
+                write!(f, "cannot apply a term of type {} to a type", ty)
+            }
+        }
+    }
+}
+
+/// A type error together with the subterm it was found at
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypeError {
+    pub term: Box<Expr>,
+    pub kind: TypeErrorKind,
+}
+
+impl TypeError {
+    pub(crate) fn new(term: &Expr, kind: TypeErrorKind) -> Self {
+        TypeError {
+            term: Box::new(term.clone()),
+            kind,
+        }
+    }
+}
+
+impl std::fmt::Display for TypeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at {}", self.kind, self.term.format(false))
+    }
+}
+
+impl std::error::Error for TypeError {}
+
+/// Compute the type of a closed, fully annotated term
+///
+/// Works on bound and unbound terms alike, since variables are looked up
+/// innermost binder first.
+pub fn type_of(expr: &Expr) -> Result<Type, TypeError> {
+    check(expr, &mut Vec::new())
+}
+
+fn check(expr: &Expr, scope: &mut Vec<(VarName, Type)>) -> Result<Type, TypeError> {
+    match expr {
+        Expr::Var(name) => scope
+            .iter()
+            .rev()
+            .find(|(bound, _)| bound == name)
+            .map(|(_, ty)| ty.clone())
+            .ok_or_else(|| TypeError::new(expr, TypeErrorKind::Unbound(name.name.clone()))),
+        Expr::Fun(arg, ty, body) => {
+            let param = ty.clone().ok_or_else(|| {
+                TypeError::new(expr, TypeErrorKind::MissingAnnotation(arg.name.clone()))
+            })?;
+            scope.push((arg.clone(), param.clone()));
+            let result = check(body, scope);
+            scope.pop();
+            Ok(Type::arrow(param, result?))
+        }
+        Expr::App(lhs, rhs) => {
+            let lhs_ty = check(lhs, scope)?;
+            let rhs_ty = check(rhs, scope)?;
+            match lhs_ty {
+                Type::Arrow(param, result) if param.alpha_eq(&rhs_ty) => Ok(*result),
+                Type::Arrow(param, _) => Err(TypeError::new(
+                    rhs,
+                    TypeErrorKind::Mismatch {
+                        expected: *param,
+                        found: rhs_ty,
+                    },
+                )),
+                Type::Base(_) | Type::Var(_) | Type::Forall(_, _) => {
+                    Err(TypeError::new(lhs, TypeErrorKind::NotAFunction(lhs_ty)))
+                }
+            }
+        }
+        Expr::Lit(_) => Ok(Type::int()),
+        Expr::Prim(op) => match op {
+            PrimOp::Add | PrimOp::Sub | PrimOp::Mul => Ok(Type::arrow(
+                Type::int(),
+                Type::arrow(Type::int(), Type::int()),
+            )),
+            // eq gives a Church boolean and ifz returns either branch, so
+            // both would need polymorphism
+            PrimOp::Eq | PrimOp::IfZero => Err(TypeError::new(expr, TypeErrorKind::Untypable(*op))),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eval::{bind_vars, NameSupply};
+    use crate::parser::{parse, parse_with, ParseOptions};
+
+    fn type_str(input: &str) -> Result<String, String> {
+        let expr = bind_vars(*parse(input).unwrap(), &mut NameSupply::new());
+        type_of(&expr)
+            .map(|ty| ty.to_string())
+            .map_err(|err| err.to_string())
+    }
+
+    #[test]
+    fn test_identity_and_constant() {
+        assert_eq!(type_str("\\x:Nat.x"), Ok("Nat->Nat".to_string()));
+        assert_eq!(
+            type_str("\\x:Nat.\\y:Bool.x"),
+            Ok("Nat->Bool->Nat".to_string())
+        );
+    }
+
+    #[test]
+    fn test_higher_order() {
+        assert_eq!(
+            type_str("\\f:Nat->Nat.\\x:Nat.f (f x)"),
+            Ok("(Nat->Nat)->Nat->Nat".to_string())
+        );
+        assert_eq!(
+            type_str("(\\f:Nat->Nat.f) (\\x:Nat.x)"),
+            Ok("Nat->Nat".to_string())
+        );
+    }
+
+    #[test]
+    fn test_inner_binder_shadows() {
+        assert_eq!(
+            type_str("\\x:Nat.\\x:Bool.x"),
+            Ok("Nat->Bool->Bool".to_string())
+        );
+    }
+
+    #[test]
+    fn test_mismatch_points_at_argument() {
+        assert_eq!(
+            type_str("\\f:Nat->Nat.\\b:Bool.\\n:Nat.f (f b)"),
+            Err("expected Nat, found Bool at b".to_string())
+        );
+        assert_eq!(
+            type_str("(\\f:Nat->Nat.f) (\\x:Bool.x)"),
+            Err("expected Nat->Nat, found Bool->Bool at (\\x:Bool.x)".to_string())
+        );
+    }
+
+    #[test]
+    fn test_not_a_function() {
+        assert_eq!(
+            type_str("\\x:Nat.\\y:Nat.x y"),
+            Err("cannot apply a term of type Nat at x".to_string())
+        );
+    }
+
+    #[test]
+    fn test_missing_annotation_and_unbound() {
+        assert_eq!(
+            type_str("\\x:Nat.\\y.x"),
+            Err("binder y has no type annotation at (\\y.x)".to_string())
+        );
+        assert_eq!(
+            type_str("\\x:Nat.f x"),
+            Err("unbound variable f at f".to_string())
+        );
+    }
+
+    #[test]
+    fn test_primitives() {
+        let options = ParseOptions {
+            primitives: true,
+            ..Default::default()
+        };
+        let expr = parse_with("\\x:Int.add x 1", options).unwrap();
+        assert_eq!(type_of(&expr), Ok(Type::arrow(Type::int(), Type::int())));
+        let expr = parse_with("ifz 0 1 2", options).unwrap();
+        assert_eq!(
+            type_of(&expr).unwrap_err().kind,
+            TypeErrorKind::Untypable(PrimOp::IfZero)
+        );
+    }
+}